@@ -1,5 +1,6 @@
 use crate::inner::MultiIndexOrderMap;
 use crate::inner::Order;
+use multi_index_map::ElementId;
 
 mod inner {
     use multi_index_map::MultiIndexMap;
@@ -33,8 +34,9 @@ fn main() {
 
     let mut map = MultiIndexOrderMap::default();
 
-    let _o1_ref: &Order = map.insert(o1);
-    let _o2_ref: &Order = map.try_insert(o2).unwrap();
+    let o1_id: ElementId = map.insert(o1);
+    let _o2_id: ElementId = map.try_insert(o2).unwrap();
+    let _o1_ref: &Order = map.get_by_element_id(o1_id).unwrap();
 
     // Set non-mutable, non mutating iter methods still work.
     let map = map;
@@ -0,0 +1,108 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// The key of a composite index as stored in its lookup table, an owned tuple of the fields it is read from.
+///
+/// Hashes and compares exactly as the tuple it wraps, so the lookup table can be queried with either the tuple,
+/// as the accessors of the index are, or with a tuple of references to the fields of an element,
+/// so the key of an element can be found without cloning its fields.
+#[doc(hidden)]
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct CompositeKey<T>(pub T);
+
+impl<T> Borrow<T> for CompositeKey<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+/// A tuple of references to the fields of a composite key,
+/// viewed as the unsized form of the key which its lookup table can be queried with.
+#[doc(hidden)]
+pub trait CompositeKeyRef {
+    type Key: ?Sized;
+
+    fn as_key(&self) -> &Self::Key;
+}
+
+// For each arity define a trait giving the fields of either an owned key or a tuple of references as references,
+//   with the lookup traits implemented on its trait object by comparing those references,
+//   which matches the derived implementations on the owned tuple.
+macro_rules! composite_key_arity {
+    ($components:ident, $($ty:ident $idx:tt),+) => {
+        #[doc(hidden)]
+        pub trait $components<$($ty),+> {
+            fn components(&self) -> ($(&$ty,)+);
+        }
+
+        impl<$($ty),+> $components<$($ty),+> for ($($ty,)+) {
+            fn components(&self) -> ($(&$ty,)+) {
+                ($(&self.$idx,)+)
+            }
+        }
+
+        impl<'a, $($ty),+> $components<$($ty),+> for ($(&'a $ty,)+) {
+            fn components(&self) -> ($(&$ty,)+) {
+                ($(self.$idx,)+)
+            }
+        }
+
+        impl<'a, $($ty: 'a),+> CompositeKeyRef for ($(&'a $ty,)+) {
+            type Key = dyn $components<$($ty),+> + 'a;
+
+            fn as_key(&self) -> &Self::Key {
+                self
+            }
+        }
+
+        impl<'a, $($ty: 'a),+> Borrow<dyn $components<$($ty),+> + 'a> for CompositeKey<($($ty,)+)> {
+            fn borrow(&self) -> &(dyn $components<$($ty),+> + 'a) {
+                &self.0
+            }
+        }
+
+        impl<'a, $($ty: Hash),+> Hash for dyn $components<$($ty),+> + 'a {
+            fn hash<S: Hasher>(&self, state: &mut S) {
+                self.components().hash(state)
+            }
+        }
+
+        impl<'a, $($ty: PartialEq),+> PartialEq for dyn $components<$($ty),+> + 'a {
+            fn eq(&self, other: &Self) -> bool {
+                self.components() == other.components()
+            }
+        }
+
+        impl<'a, $($ty: Eq),+> Eq for dyn $components<$($ty),+> + 'a {}
+
+        impl<'a, $($ty: PartialOrd),+> PartialOrd for dyn $components<$($ty),+> + 'a {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.components().partial_cmp(&other.components())
+            }
+        }
+
+        impl<'a, $($ty: Ord),+> Ord for dyn $components<$($ty),+> + 'a {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.components().cmp(&other.components())
+            }
+        }
+    };
+}
+
+composite_key_arity!(Components2, A 0, B 1);
+composite_key_arity!(Components3, A 0, B 1, C 2);
+composite_key_arity!(Components4, A 0, B 1, C 2, D 3);
+composite_key_arity!(Components5, A 0, B 1, C 2, D 3, E 4);
+composite_key_arity!(Components6, A 0, B 1, C 2, D 3, E 4, F 5);
+composite_key_arity!(Components7, A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+composite_key_arity!(Components8, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+composite_key_arity!(Components9, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+composite_key_arity!(Components10, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+composite_key_arity!(Components11, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+composite_key_arity!(Components12, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
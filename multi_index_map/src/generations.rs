@@ -0,0 +1,44 @@
+/// Generations of the positions in the backing storage, enabled by `#[multi_index_generational]`.
+///
+/// The generation of a position is bumped whenever its element is removed,
+/// so a handle recording the generation at the time it was created no longer matches once the position is reused.
+/// Generations wrap on overflow, so a handle may alias again after `u32::MAX` removals from the same position.
+#[doc(hidden)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Generations {
+    generations: Vec<u32>,
+}
+
+impl Generations {
+    /// The current generation of the position.
+    pub fn get(&self, idx: usize) -> u32 {
+        self.generations.get(idx).copied().unwrap_or(0)
+    }
+
+    pub fn insert(&mut self, idx: usize) {
+        if idx >= self.generations.len() {
+            self.generations.resize(idx + 1, 0);
+        }
+    }
+
+    /// Bump the generation of the position, it must have been inserted.
+    pub fn remove(&mut self, idx: usize) {
+        self.generations[idx] = self.generations[idx].wrapping_add(1);
+    }
+
+    // Every element is removed, so every generation is bumped rather than forgotten.
+    pub fn clear(&mut self) {
+        for generation in self.generations.iter_mut() {
+            *generation = generation.wrapping_add(1);
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.generations.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.generations.shrink_to_fit();
+    }
+}
@@ -0,0 +1,122 @@
+use crate::treap::{Augmented, Treap, NIL};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Interval<K> {
+    start: K,
+    end: K,
+    // The greatest end of any interval in the subtree rooted at this interval.
+    max_end: K,
+}
+
+impl<K: Ord + Clone> Augmented for Interval<K> {
+    fn order(&self, other: &Self) -> std::cmp::Ordering {
+        self.start.cmp(&other.start)
+    }
+
+    fn update(&mut self, left: Option<&Self>, right: Option<&Self>) {
+        let mut max_end = &self.end;
+        for child in [left, right].into_iter().flatten() {
+            if child.max_end > *max_end {
+                max_end = &child.max_end;
+            }
+        }
+        self.max_end = max_end.clone();
+    }
+}
+
+/// Lookup table for an interval index, enabled by `#[multi_index_interval]`.
+///
+/// A treap of the half-open intervals `[start, end)` of each element, ordered by start,
+/// where each node also records the greatest end within its subtree, so subtrees which cannot overlap are skipped.
+/// Nodes are stored at the position of their element in the backing storage, which also breaks ties between equal starts.
+/// Intervals with `end <= start` are empty, so never overlap or contain anything.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalTree<K> {
+    treap: Treap<Interval<K>>,
+}
+
+impl<K> Default for IntervalTree<K> {
+    fn default() -> Self {
+        Self {
+            treap: Treap::default(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> IntervalTree<K> {
+    pub fn insert(&mut self, start: K, end: K, idx: usize) {
+        let max_end = end.clone();
+        self.treap.insert(
+            Interval {
+                start,
+                end,
+                max_end,
+            },
+            idx,
+        );
+    }
+
+    /// Remove the interval of the element at the position, it must currently be present.
+    pub fn remove(&mut self, idx: usize) {
+        self.treap.remove(idx);
+    }
+
+    /// Positions of the intervals overlapping `[start, end)`, in order of their start.
+    pub fn overlapping(&self, start: &K, end: &K) -> Vec<usize> {
+        let mut idxs = Vec::new();
+        if start < end {
+            self.collect_overlapping(self.treap.root(), start, end, &mut idxs);
+        }
+        idxs
+    }
+
+    /// Positions of the intervals containing the point, in order of their start.
+    pub fn containing(&self, point: &K) -> Vec<usize> {
+        let mut idxs = Vec::new();
+        self.collect_containing(self.treap.root(), point, &mut idxs);
+        idxs
+    }
+
+    pub fn clear(&mut self) {
+        self.treap.clear();
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.treap.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.treap.shrink_to_fit();
+    }
+
+    fn collect_overlapping(&self, t: usize, start: &K, end: &K, idxs: &mut Vec<usize>) {
+        if t == NIL || self.treap.value(t).max_end <= *start {
+            return;
+        }
+        let interval = self.treap.value(t);
+        self.collect_overlapping(self.treap.left(t), start, end, idxs);
+        if interval.start < *end {
+            if interval.start < interval.end && *start < interval.end {
+                idxs.push(t);
+            }
+            self.collect_overlapping(self.treap.right(t), start, end, idxs);
+        }
+    }
+
+    fn collect_containing(&self, t: usize, point: &K, idxs: &mut Vec<usize>) {
+        if t == NIL || self.treap.value(t).max_end <= *point {
+            return;
+        }
+        let interval = self.treap.value(t);
+        self.collect_containing(self.treap.left(t), point, idxs);
+        if interval.start <= *point {
+            if *point < interval.end {
+                idxs.push(t);
+            }
+            self.collect_containing(self.treap.right(t), point, idxs);
+        }
+    }
+}
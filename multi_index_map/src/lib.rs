@@ -1,20 +1,184 @@
 pub use multi_index_map_derive::MultiIndexMap;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub struct UniquenessError<T>(pub T);
+mod composite;
+mod generations;
+mod interval;
+mod prefix;
+mod random_access;
+mod ranked;
+mod sequenced;
+mod treap;
+
+#[doc(hidden)]
+pub use composite::*;
+#[doc(hidden)]
+pub use generations::Generations;
+#[doc(hidden)]
+pub use interval::IntervalTree;
+#[doc(hidden)]
+pub use prefix::prefix_upper_bound;
+#[doc(hidden)]
+pub use random_access::{RandomAccess, RandomAccessIter};
+#[doc(hidden)]
+pub use ranked::RankTree;
+#[doc(hidden)]
+pub use sequenced::{Sequence, SequenceIter};
+
+/// Returned when inserting or modifying an element would violate a uniqueness constraint, in which case the map is not changed.
+///
+/// `element` holds the rejected element, or `()` when modifying,
+/// and `conflicts()` lists every unique index the element conflicts on.
+///
+/// A map with a bitmap index can only hold elements at positions up to `u32::MAX` in its backing storage,
+/// an element inserted when no such position is free is also rejected, with no conflicts.
+#[derive(Clone, PartialEq, Eq)]
+pub struct UniquenessError<T> {
+    pub element: T,
+    conflicts: Vec<UniquenessConflict>,
+}
+
+impl<T> UniquenessError<T> {
+    #[doc(hidden)]
+    pub fn new(element: T, conflicts: Vec<UniquenessConflict>) -> Self {
+        Self { element, conflicts }
+    }
+
+    /// The unique indexes the element conflicts on, in the order the indexes are declared.
+    pub fn conflicts(&self) -> &[UniquenessConflict] {
+        &self.conflicts
+    }
+}
 
 impl<T> core::fmt::Display for UniquenessError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.conflicts.is_empty() {
+            return write!(
+                f,
+                "Unable to insert element, no position up to u32::MAX is free for the bitmap indexes"
+            );
+        }
+        write!(f, "Uniqueness constraint violated")?;
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            let separator = if i == 0 { " on" } else { "," };
+            write!(
+                f,
+                "{separator} index '{}' by the element with id {}",
+                conflict.index,
+                conflict.element_id.index()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> core::fmt::Debug for UniquenessError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("UniquenessViolated")
+            .field(&self.conflicts)
+            .finish()
+    }
+}
+
+/// A unique index on which an element conflicts with another element already in the map.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UniquenessConflict {
+    index: &'static str,
+    element_id: ElementId,
+}
+
+impl UniquenessConflict {
+    #[doc(hidden)]
+    pub fn new(index: &'static str, element_id: ElementId) -> Self {
+        Self { index, element_id }
+    }
+
+    /// The name of the index, which is the name of the field for indexes on a single field.
+    pub fn index(&self) -> &'static str {
+        self.index
+    }
+
+    /// The id of the element already holding the key.
+    pub fn element_id(&self) -> ElementId {
+        self.element_id
+    }
+}
+
+/// Returned by `try_extend` when any element of a batch violates a uniqueness constraint, in which case none are inserted.
+///
+/// `conflicts` holds the positions within the batch of every element whose key in a unique index
+/// is already in the map, or is also the key of another element of the batch, in ascending order.
+/// When elements of the batch share a key, every one of them is listed, including the first.
+/// `elements` holds the whole batch, so it can be corrected and retried.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BatchError<T> {
+    pub elements: Vec<T>,
+    pub conflicts: Vec<usize>,
+}
+
+impl<T> core::fmt::Display for BatchError<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(
             f,
-            "Unable to insert element, uniqueness constraint violated"
+            "Unable to insert batch, uniqueness constraint violated by the elements at positions {:?}",
+            self.conflicts
         )
     }
 }
 
-impl<T> core::fmt::Debug for UniquenessError<T> {
+impl<T> core::fmt::Debug for BatchError<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("UniquenessViolated").finish()
+        f.debug_struct("BatchUniquenessViolated")
+            .field("conflicts", &self.conflicts)
+            .finish()
+    }
+}
+
+/// Identifies an element by its position in the backing storage of a map, returned by `insert` and `try_insert`.
+///
+/// The position is vacated when the element is removed, and may be reused by a later insert,
+/// so an id kept after its element is removed may refer to a different element.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementId(usize);
+
+impl ElementId {
+    #[doc(hidden)]
+    pub fn from_index(index: usize) -> Self {
+        Self(index)
+    }
+
+    /// The position of the element in the backing storage, as yielded by `iter`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Identifies an element by its position in the backing storage of a map alongside the generation of that position,
+/// returned by `insert_with_handle` and `iter_with_handles` on maps with `#[multi_index_generational]`.
+///
+/// The generation changes whenever the element at the position is removed,
+/// so a handle kept after its element is removed never refers to a different element.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementHandle {
+    id: ElementId,
+    generation: u32,
+}
+
+impl ElementHandle {
+    #[doc(hidden)]
+    pub fn new(id: ElementId, generation: u32) -> Self {
+        Self { id, generation }
+    }
+
+    /// The id of the element, which may refer to a different element once this handle is stale.
+    pub fn element_id(self) -> ElementId {
+        self.id
+    }
+
+    #[doc(hidden)]
+    pub fn generation(self) -> u32 {
+        self.generation
     }
 }
 
@@ -23,3 +187,7 @@ impl<T> core::fmt::Debug for UniquenessError<T> {
 pub use rustc_hash;
 #[doc(hidden)]
 pub use slab;
+
+// Public, as the bitmaps of bitmap indexes can be combined by callers before looking up the elements.
+#[cfg(feature = "bitmap")]
+pub use roaring;
@@ -0,0 +1,20 @@
+/// Get the smallest string greater than every string starting with the given prefix,
+/// used as the exclusive upper bound of a range over an ordered index of strings.
+///
+/// Trailing characters which cannot be incremented are dropped, if no characters remain there is no such bound.
+#[doc(hidden)]
+pub fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut upper = prefix.to_string();
+    while let Some(last) = upper.pop() {
+        // Skip over the surrogate range, which are not valid chars.
+        let next = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            upper.push(next);
+            return Some(upper);
+        }
+    }
+    None
+}
@@ -0,0 +1,347 @@
+use crate::treap::{priority, NIL};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node {
+    priority: u64,
+    left: usize,
+    right: usize,
+    parent: usize,
+    size: usize,
+}
+
+/// Lookup table for the random access index, enabled by `#[multi_index_random_access]`.
+///
+/// Records the positions in the backing storage in logical order as an implicit treap,
+/// which is ordered by where each node sits in the tree rather than by any key.
+/// Each node records the size of its subtree and its parent, with the nodes stored in a `Vec` indexed by position,
+/// so looking up by logical position, finding the logical position of an element,
+/// and inserting or removing anywhere are all logarithmic-time in expectation.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RandomAccess {
+    nodes: Vec<Option<Node>>,
+    root: usize,
+}
+
+impl Default for RandomAccess {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: NIL,
+        }
+    }
+}
+
+impl RandomAccess {
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    /// The position in the backing storage of the element at the given logical position.
+    pub fn get(&self, mut position: usize) -> Option<usize> {
+        let mut t = self.root;
+        while t != NIL {
+            let node = self.node(t);
+            let left = self.size(node.left);
+            match position.cmp(&left) {
+                std::cmp::Ordering::Less => t = node.left,
+                std::cmp::Ordering::Equal => return Some(t),
+                std::cmp::Ordering::Greater => {
+                    position -= left + 1;
+                    t = node.right;
+                }
+            }
+        }
+        None
+    }
+
+    /// The logical position of the given position in the backing storage, it must currently be present.
+    pub fn position(&self, idx: usize) -> usize {
+        // Count the nodes before this one in its own subtree, then in each subtree it is the right child of.
+        let mut position = self.size(self.node(idx).left);
+        let mut t = idx;
+        let mut parent = self.node(t).parent;
+        while parent != NIL {
+            let node = self.node(parent);
+            if node.right == t {
+                position += self.size(node.left) + 1;
+            }
+            t = parent;
+            parent = node.parent;
+        }
+        position
+    }
+
+    pub fn push(&mut self, idx: usize) {
+        if idx >= self.nodes.len() {
+            self.nodes.resize(idx + 1, None);
+        }
+        self.nodes[idx] = Some(Node {
+            priority: priority(idx),
+            left: NIL,
+            right: NIL,
+            parent: NIL,
+            size: 1,
+        });
+        self.root = self.merge(self.root, idx);
+        self.set_parent(self.root, NIL);
+    }
+
+    /// Remove the position from the index, it must currently be present.
+    pub fn remove(&mut self, idx: usize) {
+        self.detach(idx);
+        self.nodes[idx] = None;
+    }
+
+    /// Move the position to the given logical position, shifting the elements in between.
+    pub fn move_to(&mut self, idx: usize, position: usize) {
+        self.detach(idx);
+        let priority = self.node(idx).priority;
+        self.nodes[idx] = Some(Node {
+            priority,
+            left: NIL,
+            right: NIL,
+            parent: NIL,
+            size: 1,
+        });
+        let (before, after) = self.split(self.root, position);
+        let before = self.merge(before, idx);
+        self.root = self.merge(before, after);
+        self.set_parent(self.root, NIL);
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+    }
+
+    pub fn iter(&self) -> RandomAccessIter<'_> {
+        let mut front = self.root;
+        let mut back = self.root;
+        if self.root != NIL {
+            while self.node(front).left != NIL {
+                front = self.node(front).left;
+            }
+            while self.node(back).right != NIL {
+                back = self.node(back).right;
+            }
+        }
+        RandomAccessIter {
+            index: self,
+            front,
+            back,
+            remaining: self.len(),
+        }
+    }
+
+    fn node(&self, idx: usize) -> &Node {
+        self.nodes[idx]
+            .as_ref()
+            .expect("Internal invariants broken, random access index references a missing node")
+    }
+
+    fn node_mut(&mut self, idx: usize) -> &mut Node {
+        self.nodes[idx]
+            .as_mut()
+            .expect("Internal invariants broken, random access index references a missing node")
+    }
+
+    fn size(&self, t: usize) -> usize {
+        match t {
+            NIL => 0,
+            t => self.node(t).size,
+        }
+    }
+
+    fn set_parent(&mut self, t: usize, parent: usize) {
+        if t != NIL {
+            self.node_mut(t).parent = parent;
+        }
+    }
+
+    fn set_left(&mut self, t: usize, left: usize) {
+        self.node_mut(t).left = left;
+        self.set_parent(left, t);
+    }
+
+    fn set_right(&mut self, t: usize, right: usize) {
+        self.node_mut(t).right = right;
+        self.set_parent(right, t);
+    }
+
+    // Recompute the size of the subtree rooted at `t` from its children.
+    fn update(&mut self, t: usize) {
+        let node = self.node(t);
+        let size = self.size(node.left) + self.size(node.right) + 1;
+        self.node_mut(t).size = size;
+    }
+
+    // Unlink the node from the tree, putting the merge of its children in its place,
+    //   and shrink the subtree of each of its ancestors.
+    fn detach(&mut self, idx: usize) {
+        let Node {
+            left,
+            right,
+            parent,
+            ..
+        } = *self.node(idx);
+        let merged = self.merge(left, right);
+        if parent == NIL {
+            self.root = merged;
+            self.set_parent(merged, NIL);
+            return;
+        }
+        if self.node(parent).left == idx {
+            self.set_left(parent, merged);
+        } else {
+            self.set_right(parent, merged);
+        }
+        let mut t = parent;
+        while t != NIL {
+            let node = self.node_mut(t);
+            node.size -= 1;
+            t = node.parent;
+        }
+    }
+
+    // Split the subtree rooted at `t` into its first `count` nodes, and the rest.
+    // The parent of each returned subtree is left to the caller.
+    fn split(&mut self, t: usize, count: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let left = self.node(t).left;
+        let left_size = self.size(left);
+        if count <= left_size {
+            let (before, after) = self.split(left, count);
+            self.set_left(t, after);
+            self.update(t);
+            (before, t)
+        } else {
+            let right = self.node(t).right;
+            let (before, after) = self.split(right, count - left_size - 1);
+            self.set_right(t, before);
+            self.update(t);
+            (t, after)
+        }
+    }
+
+    // Merge two subtrees, placing every node in `left` before every node in `right`.
+    // The parent of the returned subtree is left to the caller.
+    fn merge(&mut self, left: usize, right: usize) -> usize {
+        if left == NIL {
+            return right;
+        }
+        if right == NIL {
+            return left;
+        }
+        if self.node(left).priority > self.node(right).priority {
+            let merged = self.merge(self.node(left).right, right);
+            self.set_right(left, merged);
+            self.update(left);
+            left
+        } else {
+            let merged = self.merge(left, self.node(right).left);
+            self.set_left(right, merged);
+            self.update(right);
+            right
+        }
+    }
+
+    // The node after `t` in logical order, found by walking the tree, so amortised constant-time when iterating.
+    fn successor(&self, mut t: usize) -> usize {
+        let right = self.node(t).right;
+        if right != NIL {
+            t = right;
+            while self.node(t).left != NIL {
+                t = self.node(t).left;
+            }
+            return t;
+        }
+        loop {
+            let parent = self.node(t).parent;
+            if parent == NIL || self.node(parent).left == t {
+                return parent;
+            }
+            t = parent;
+        }
+    }
+
+    // The node before `t` in logical order, mirroring `successor`.
+    fn predecessor(&self, mut t: usize) -> usize {
+        let left = self.node(t).left;
+        if left != NIL {
+            t = left;
+            while self.node(t).right != NIL {
+                t = self.node(t).right;
+            }
+            return t;
+        }
+        loop {
+            let parent = self.node(t).parent;
+            if parent == NIL || self.node(parent).right == t {
+                return parent;
+            }
+            t = parent;
+        }
+    }
+}
+
+/// Iterator over the positions in the backing storage, in logical order.
+#[doc(hidden)]
+pub struct RandomAccessIter<'a> {
+    index: &'a RandomAccess,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl Iterator for RandomAccessIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.front;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.front = self.index.successor(idx);
+        }
+        Some(idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for RandomAccessIter<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.back;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.back = self.index.predecessor(idx);
+        }
+        Some(idx)
+    }
+}
+
+impl ExactSizeIterator for RandomAccessIter<'_> {}
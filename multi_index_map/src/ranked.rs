@@ -0,0 +1,107 @@
+use crate::treap::{Augmented, Treap, NIL};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Ranked<K> {
+    key: K,
+    // The number of keys in the subtree rooted at this key, including itself.
+    size: usize,
+}
+
+impl<K: Ord> Augmented for Ranked<K> {
+    fn order(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+
+    fn update(&mut self, left: Option<&Self>, right: Option<&Self>) {
+        self.size = left.map_or(0, |l| l.size) + right.map_or(0, |r| r.size) + 1;
+    }
+}
+
+/// Order statistics for a ranked index, enabled by `#[multi_index(ordered_unique, ranked)]`.
+///
+/// A treap of the keys of each element, where each node also records the size of its subtree,
+/// so the rank of a key and the element at a rank are both found in logarithmic time.
+/// Nodes are stored at the position of their element in the backing storage, which also breaks ties between equal keys.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankTree<K> {
+    treap: Treap<Ranked<K>>,
+}
+
+impl<K> Default for RankTree<K> {
+    fn default() -> Self {
+        Self {
+            treap: Treap::default(),
+        }
+    }
+}
+
+impl<K: Ord> RankTree<K> {
+    pub fn insert(&mut self, key: K, idx: usize) {
+        self.treap.insert(Ranked { key, size: 1 }, idx);
+    }
+
+    /// Remove the key of the element at the position, it must currently be present.
+    pub fn remove(&mut self, idx: usize) {
+        self.treap.remove(idx);
+    }
+
+    /// The key of the element at the position, it must currently be present.
+    pub fn key(&self, idx: usize) -> &K {
+        &self.treap.value(idx).key
+    }
+
+    /// The number of keys less than the given key.
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut t = self.treap.root();
+        while t != NIL {
+            if self.treap.value(t).key.borrow() < key {
+                rank += self.size(self.treap.left(t)) + 1;
+                t = self.treap.right(t);
+            } else {
+                t = self.treap.left(t);
+            }
+        }
+        rank
+    }
+
+    /// The position in the backing storage of the element with the nth smallest key, counting from zero.
+    pub fn nth(&self, mut n: usize) -> Option<usize> {
+        let mut t = self.treap.root();
+        while t != NIL {
+            let left_size = self.size(self.treap.left(t));
+            match n.cmp(&left_size) {
+                std::cmp::Ordering::Less => t = self.treap.left(t),
+                std::cmp::Ordering::Equal => return Some(t),
+                std::cmp::Ordering::Greater => {
+                    n -= left_size + 1;
+                    t = self.treap.right(t);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn clear(&mut self) {
+        self.treap.clear();
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.treap.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.treap.shrink_to_fit();
+    }
+
+    fn size(&self, t: usize) -> usize {
+        self.treap.get(t).map_or(0, |ranked| ranked.size)
+    }
+}
@@ -0,0 +1,185 @@
+// Marker for a missing link, ie. the end of the sequence.
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Link {
+    prev: usize,
+    next: usize,
+}
+
+/// Lookup table for the sequenced index, enabled by `#[multi_index_sequenced]`.
+///
+/// Records the order of the positions in the backing storage as a doubly linked list,
+/// with the links stored in a `Vec` indexed by position, so every operation is constant-time.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sequence {
+    links: Vec<Link>,
+    head: usize,
+    tail: usize,
+    len: usize,
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self {
+            links: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            len: 0,
+        }
+    }
+}
+
+impl Sequence {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<usize> {
+        (self.head != NIL).then_some(self.head)
+    }
+
+    pub fn back(&self) -> Option<usize> {
+        (self.tail != NIL).then_some(self.tail)
+    }
+
+    pub fn push_back(&mut self, idx: usize) {
+        self.ensure_link(idx);
+        self.links[idx] = Link {
+            prev: self.tail,
+            next: NIL,
+        };
+        match self.tail {
+            NIL => self.head = idx,
+            tail => self.links[tail].next = idx,
+        }
+        self.tail = idx;
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, idx: usize) {
+        self.ensure_link(idx);
+        self.links[idx] = Link {
+            prev: NIL,
+            next: self.head,
+        };
+        match self.head {
+            NIL => self.tail = idx,
+            head => self.links[head].prev = idx,
+        }
+        self.head = idx;
+        self.len += 1;
+    }
+
+    /// Unlink the position from the sequence, it must currently be present.
+    pub fn remove(&mut self, idx: usize) {
+        let Link { prev, next } = self.links[idx];
+        match prev {
+            NIL => self.head = next,
+            prev => self.links[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.links[next].prev = prev,
+        }
+        self.len -= 1;
+    }
+
+    pub fn move_to_front(&mut self, idx: usize) {
+        if self.head != idx {
+            self.remove(idx);
+            self.push_front(idx);
+        }
+    }
+
+    pub fn move_to_back(&mut self, idx: usize) {
+        if self.tail != idx {
+            self.remove(idx);
+            self.push_back(idx);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.links.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.len = 0;
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.links.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.links.shrink_to_fit();
+    }
+
+    pub fn iter(&self) -> SequenceIter<'_> {
+        SequenceIter {
+            links: &self.links,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    fn ensure_link(&mut self, idx: usize) {
+        if idx >= self.links.len() {
+            self.links.resize(
+                idx + 1,
+                Link {
+                    prev: NIL,
+                    next: NIL,
+                },
+            );
+        }
+    }
+}
+
+/// Iterator over the positions in the backing storage, in sequence order.
+#[doc(hidden)]
+pub struct SequenceIter<'a> {
+    links: &'a [Link],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl Iterator for SequenceIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.front;
+        self.front = self.links[idx].next;
+        self.remaining -= 1;
+        Some(idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for SequenceIter<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.back;
+        self.back = self.links[idx].prev;
+        self.remaining -= 1;
+        Some(idx)
+    }
+}
+
+impl ExactSizeIterator for SequenceIter<'_> {}
@@ -0,0 +1,220 @@
+// Marker for a missing child, ie. an empty subtree.
+pub(crate) const NIL: usize = usize::MAX;
+
+// The values held by a treap, each of which records a summary of the subtree rooted at its node,
+//   eg. the greatest end of the intervals in an interval tree, or the number of keys in a rank tree.
+pub(crate) trait Augmented {
+    // The order of the values, ties between equal values are broken by position.
+    fn order(&self, other: &Self) -> std::cmp::Ordering;
+
+    // Recompute the summary of the subtree rooted at this value, from the values of its children.
+    fn update(&mut self, left: Option<&Self>, right: Option<&Self>);
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node<T> {
+    value: T,
+    priority: u64,
+    left: usize,
+    right: usize,
+}
+
+// A treap of augmented values, shared by the interval and ranked indexes.
+// Nodes are stored at the position of their element in the backing storage, which also breaks ties between equal values.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Treap<T> {
+    nodes: Vec<Option<Node<T>>>,
+    root: usize,
+}
+
+impl<T> Default for Treap<T> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: NIL,
+        }
+    }
+}
+
+impl<T: Augmented> Treap<T> {
+    pub(crate) fn insert(&mut self, value: T, idx: usize) {
+        if idx >= self.nodes.len() {
+            self.nodes.resize_with(idx + 1, || None);
+        }
+        self.nodes[idx] = Some(Node {
+            value,
+            priority: priority(idx),
+            left: NIL,
+            right: NIL,
+        });
+        self.update(idx);
+        self.root = self.insert_node(self.root, idx);
+    }
+
+    // Remove the value at the position, it must currently be present.
+    pub(crate) fn remove(&mut self, idx: usize) {
+        self.root = self.remove_node(self.root, idx);
+        self.nodes[idx] = None;
+    }
+
+    pub(crate) fn root(&self) -> usize {
+        self.root
+    }
+
+    // The value at the position, it must currently be present.
+    pub(crate) fn value(&self, idx: usize) -> &T {
+        &self.node(idx).value
+    }
+
+    // The value at the position, or None for an empty subtree.
+    pub(crate) fn get(&self, t: usize) -> Option<&T> {
+        match t {
+            NIL => None,
+            t => Some(self.value(t)),
+        }
+    }
+
+    pub(crate) fn left(&self, t: usize) -> usize {
+        self.node(t).left
+    }
+
+    pub(crate) fn right(&self, t: usize) -> usize {
+        self.node(t).right
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+    }
+
+    fn node(&self, idx: usize) -> &Node<T> {
+        self.nodes[idx]
+            .as_ref()
+            .expect("Internal invariants broken, treap references a missing node")
+    }
+
+    fn node_mut(&mut self, idx: usize) -> &mut Node<T> {
+        self.nodes[idx]
+            .as_mut()
+            .expect("Internal invariants broken, treap references a missing node")
+    }
+
+    // Whether node `a` is ordered before node `b`, by value and then by position.
+    fn is_before(&self, a: usize, b: usize) -> bool {
+        match self.value(a).order(self.value(b)) {
+            std::cmp::Ordering::Equal => a < b,
+            ordering => ordering == std::cmp::Ordering::Less,
+        }
+    }
+
+    // Recompute the summary of the subtree rooted at `t` from its children.
+    // The node is taken out of the storage while updating, so its children can be borrowed alongside it.
+    fn update(&mut self, t: usize) {
+        let mut node = self.nodes[t]
+            .take()
+            .expect("Internal invariants broken, treap references a missing node");
+        node.value.update(self.get(node.left), self.get(node.right));
+        self.nodes[t] = Some(node);
+    }
+
+    fn insert_node(&mut self, t: usize, idx: usize) -> usize {
+        if t == NIL {
+            return idx;
+        }
+        if self.node(idx).priority > self.node(t).priority {
+            let (left, right) = self.split(t, idx);
+            let node = self.node_mut(idx);
+            node.left = left;
+            node.right = right;
+            self.update(idx);
+            return idx;
+        }
+        if self.is_before(idx, t) {
+            let left = self.insert_node(self.node(t).left, idx);
+            self.node_mut(t).left = left;
+        } else {
+            let right = self.insert_node(self.node(t).right, idx);
+            self.node_mut(t).right = right;
+        }
+        self.update(t);
+        t
+    }
+
+    fn remove_node(&mut self, t: usize, idx: usize) -> usize {
+        if t == NIL {
+            return NIL;
+        }
+        if t == idx {
+            let node = self.node(t);
+            return self.merge(node.left, node.right);
+        }
+        if self.is_before(idx, t) {
+            let left = self.remove_node(self.node(t).left, idx);
+            self.node_mut(t).left = left;
+        } else {
+            let right = self.remove_node(self.node(t).right, idx);
+            self.node_mut(t).right = right;
+        }
+        self.update(t);
+        t
+    }
+
+    // Split the subtree rooted at `t` into the nodes ordered before `idx`, and those ordered after it.
+    fn split(&mut self, t: usize, idx: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        if self.is_before(t, idx) {
+            let (left, right) = self.split(self.node(t).right, idx);
+            self.node_mut(t).right = left;
+            self.update(t);
+            (t, right)
+        } else {
+            let (left, right) = self.split(self.node(t).left, idx);
+            self.node_mut(t).left = right;
+            self.update(t);
+            (left, t)
+        }
+    }
+
+    // Merge two subtrees, where every node in `left` is ordered before every node in `right`.
+    fn merge(&mut self, left: usize, right: usize) -> usize {
+        if left == NIL {
+            return right;
+        }
+        if right == NIL {
+            return left;
+        }
+        if self.node(left).priority > self.node(right).priority {
+            let merged = self.merge(self.node(left).right, right);
+            self.node_mut(left).right = merged;
+            self.update(left);
+            left
+        } else {
+            let merged = self.merge(left, self.node(right).left);
+            self.node_mut(right).left = merged;
+            self.update(right);
+            right
+        }
+    }
+}
+
+// Pseudo-random priority for the node at the position, using the SplitMix64 finalizer,
+//   so the treap is balanced in expectation regardless of the order elements are inserted in.
+// Also used by the implicit treap of the random access index.
+pub(crate) fn priority(idx: usize) -> u64 {
+    let mut z = (idx as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "bitmap")]
+use multi_index_map::MultiIndexMap;
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+enum Status {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct Order {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(bitmap)]
+    side: Side,
+    #[multi_index(bitmap)]
+    status: Status,
+}
+
+#[test]
+fn test_get_and_count() {
+    let mut map = MultiIndexOrderMap::default();
+    for id in 0..12 {
+        map.insert(Order {
+            id,
+            side: if id % 2 == 0 { Side::Buy } else { Side::Sell },
+            status: match id % 3 {
+                0 => Status::Open,
+                1 => Status::Filled,
+                _ => Status::Cancelled,
+            },
+        });
+    }
+
+    assert_eq!(
+        map.get_by_side(&Side::Buy)
+            .into_iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![0, 2, 4, 6, 8, 10]
+    );
+    assert_eq!(
+        map.get_by_status(&Status::Filled)
+            .into_iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![1, 4, 7, 10]
+    );
+    assert_eq!(map.count_by_side(&Side::Sell), 6);
+    assert_eq!(map.count_by_status(&Status::Open), 4);
+    assert_eq!(map.iter_by_side().count(), 12);
+    assert_eq!(map.bitmap_by_status(&Status::Cancelled).unwrap().len(), 4);
+}
+
+#[test]
+fn test_intersection() {
+    let mut map = MultiIndexOrderMap::default();
+    for id in 0..12 {
+        map.insert(Order {
+            id,
+            side: if id % 2 == 0 { Side::Buy } else { Side::Sell },
+            status: match id % 3 {
+                0 => Status::Open,
+                1 => Status::Filled,
+                _ => Status::Cancelled,
+            },
+        });
+    }
+
+    let open_buys =
+        map.bitmap_by_side(&Side::Buy).unwrap() & map.bitmap_by_status(&Status::Open).unwrap();
+    assert_eq!(
+        map.iter_bitmap(&open_buys)
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![0, 6]
+    );
+
+    let not_open = map.bitmap_by_status(&Status::Filled).unwrap()
+        | map.bitmap_by_status(&Status::Cancelled).unwrap();
+    let sells_not_open = map.bitmap_by_side(&Side::Sell).unwrap() & &not_open;
+    assert_eq!(
+        map.iter_bitmap(&sells_not_open)
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![1, 5, 7, 11]
+    );
+}
+
+#[test]
+fn test_remove_and_modify() {
+    let mut map = MultiIndexOrderMap::default();
+    for id in 0..12 {
+        map.insert(Order {
+            id,
+            side: if id % 2 == 0 { Side::Buy } else { Side::Sell },
+            status: match id % 3 {
+                0 => Status::Open,
+                1 => Status::Filled,
+                _ => Status::Cancelled,
+            },
+        });
+    }
+
+    map.remove_by_id(&0);
+    assert_eq!(map.count_by_side(&Side::Buy), 5);
+    assert_eq!(map.count_by_status(&Status::Open), 3);
+
+    map.modify_by_id(&3, |o| o.status = Status::Filled);
+    assert_eq!(
+        map.get_by_status(&Status::Open)
+            .into_iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![6, 9]
+    );
+    assert_eq!(map.count_by_status(&Status::Filled), 5);
+
+    let cancelled = map.remove_by_status(&Status::Cancelled);
+    assert_eq!(cancelled.len(), 4);
+    assert_eq!(map.count_by_status(&Status::Cancelled), 0);
+    assert!(map.bitmap_by_status(&Status::Cancelled).is_none());
+    assert!(!map.contains_status(&Status::Cancelled));
+    assert_eq!(map.distinct_count_status(), 2);
+    assert_eq!(map.len(), 7);
+    assert_eq!(
+        map.count_by_side(&Side::Buy) + map.count_by_side(&Side::Sell),
+        7
+    );
+
+    let modified = map.modify_by_status(&Status::Open, |o| o.status = Status::Cancelled);
+    assert_eq!(modified.len(), 2);
+    assert_eq!(map.count_by_status(&Status::Open), 0);
+    assert_eq!(
+        map.get_by_status(&Status::Cancelled)
+            .into_iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![6, 9]
+    );
+
+    // Removed positions are reused by later inserts.
+    map.insert(Order {
+        id: 20,
+        side: Side::Sell,
+        status: Status::Open,
+    });
+    assert_eq!(
+        map.get_by_status(&Status::Open)
+            .into_iter()
+            .map(|o| o.id)
+            .collect::<Vec<_>>(),
+        vec![20]
+    );
+    assert_eq!(map.clone().len(), 8);
+
+    map.clear();
+    assert_eq!(map.count_by_side(&Side::Sell), 0);
+    assert_eq!(map.iter_by_status().count(), 0);
+}
@@ -0,0 +1,85 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(ordered_unique)]
+    price: u32,
+    #[multi_index(ordered_non_unique)]
+    level: String,
+}
+
+#[test]
+fn test_unique_bounds() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (price, level) in [(10, "b"), (20, "b"), (30, "d"), (40, "f")] {
+        map.insert(TestElement {
+            price,
+            level: level.to_string(),
+        });
+    }
+
+    assert_eq!(map.first_by_price().map(|e| e.price), Some(10));
+    assert_eq!(map.last_by_price().map(|e| e.price), Some(40));
+
+    assert_eq!(map.ceil_by_price(&21).map(|e| e.price), Some(30));
+    assert_eq!(map.ceil_by_price(&41).map(|e| e.price), None);
+    assert_eq!(map.floor_by_price(&19).map(|e| e.price), Some(10));
+    assert_eq!(map.floor_by_price(&9).map(|e| e.price), None);
+
+    assert_eq!(map.next_after_price(&40).map(|e| e.price), None);
+    assert_eq!(map.prev_before_price(&10).map(|e| e.price), None);
+
+    let empty = MultiIndexTestElementMap::default();
+    assert_eq!(empty.first_by_price(), None);
+    assert_eq!(empty.last_by_price(), None);
+}
+
+#[test]
+fn test_bounds_at_equal_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (price, level) in [(10, "b"), (20, "b"), (30, "d")] {
+        map.insert(TestElement {
+            price,
+            level: level.to_string(),
+        });
+    }
+
+    // ceil_by_ and floor_by_ include an equal key, next_after_ and prev_before_ skip past it.
+    assert_eq!(map.ceil_by_price(&20).map(|e| e.price), Some(20));
+    assert_eq!(map.floor_by_price(&20).map(|e| e.price), Some(20));
+    assert_eq!(map.next_after_price(&20).map(|e| e.price), Some(30));
+    assert_eq!(map.prev_before_price(&20).map(|e| e.price), Some(10));
+
+    let prices = |elems: Vec<&TestElement>| elems.iter().map(|e| e.price).collect::<Vec<_>>();
+    assert_eq!(prices(map.ceil_by_level("b")), vec![10, 20]);
+    assert_eq!(prices(map.floor_by_level("b")), vec![10, 20]);
+    assert_eq!(prices(map.next_after_level("b")), vec![30]);
+    assert!(map.prev_before_level("b").is_empty());
+}
+
+#[test]
+fn test_non_unique_bounds() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (price, level) in [(10, "b"), (20, "b"), (30, "d"), (40, "f")] {
+        map.insert(TestElement {
+            price,
+            level: level.to_string(),
+        });
+    }
+    let prices = |elems: Vec<&TestElement>| elems.iter().map(|e| e.price).collect::<Vec<_>>();
+
+    assert_eq!(prices(map.first_by_level()), vec![10, 20]);
+    assert_eq!(prices(map.last_by_level()), vec![40]);
+
+    assert_eq!(prices(map.ceil_by_level("c")), vec![30]);
+    assert_eq!(prices(map.floor_by_level("c")), vec![10, 20]);
+    assert_eq!(prices(map.prev_before_level("d")), vec![10, 20]);
+    assert!(map.next_after_level("f").is_empty());
+
+    map.remove_by_level(&"b".to_string());
+    map.modify_by_price(&40, |e| e.level = "a".to_string());
+    assert_eq!(prices(map.first_by_level()), vec![40]);
+    assert_eq!(prices(map.last_by_level()), vec![30]);
+    assert_eq!(map.first_by_price().map(|e| e.price), Some(30));
+}
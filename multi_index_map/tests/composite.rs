@@ -0,0 +1,306 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+#[multi_index_composite(trader_ts(ordered_unique, fields(trader_name, timestamp)))]
+#[multi_index_composite(trader_side(hashed_non_unique, fields(trader_name, buy)))]
+struct Order {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    buy: bool,
+    volume: u64,
+}
+
+#[test]
+fn test_composite_unique_insert_and_get() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 10,
+        trader_name: "Bob".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 3,
+        timestamp: 11,
+        trader_name: "Alice".to_string(),
+        buy: false,
+        volume: 100,
+    });
+
+    let elem = map.get_by_trader_ts(&("Alice".to_string(), 10)).unwrap();
+    assert_eq!(elem.order_id, 1);
+    assert_eq!(map.get_by_trader_ts(&("Bob".to_string(), 11)), None);
+
+    // The same trader and timestamp violates the composite uniqueness constraint,
+    //   even though each field on its own is allowed to repeat.
+    let rejected = map
+        .try_insert(Order {
+            order_id: 4,
+            timestamp: 10,
+            trader_name: "Alice".to_string(),
+            buy: false,
+            volume: 100,
+        })
+        .unwrap_err();
+    assert_eq!(rejected.element.order_id, 4);
+    assert_eq!(map.len(), 3);
+    assert!(map.get_by_order_id(&4).is_none());
+}
+
+#[test]
+fn test_composite_non_unique_get_and_remove() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 11,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 3,
+        timestamp: 12,
+        trader_name: "Alice".to_string(),
+        buy: false,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 4,
+        timestamp: 13,
+        trader_name: "Bob".to_string(),
+        buy: true,
+        volume: 100,
+    });
+
+    assert_eq!(
+        map.get_by_trader_side(&("Alice".to_string(), true)).len(),
+        2
+    );
+
+    let removed = map.remove_by_trader_side(&("Alice".to_string(), true));
+    assert_eq!(removed.len(), 2);
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_trader_ts(&("Alice".to_string(), 10)).is_none());
+    assert!(map.get_by_trader_ts(&("Alice".to_string(), 12)).is_some());
+    assert_eq!(map.get_by_trader_name("Alice").len(), 1);
+}
+
+#[test]
+fn test_remove_by_field_cleans_composite() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 11,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+
+    map.remove_by_order_id(&1);
+    assert!(map.get_by_trader_ts(&("Alice".to_string(), 10)).is_none());
+    assert_eq!(
+        map.get_by_trader_side(&("Alice".to_string(), true)).len(),
+        1
+    );
+
+    // The freed key can be reused.
+    map.insert(Order {
+        order_id: 3,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    assert_eq!(
+        map.get_by_trader_ts(&("Alice".to_string(), 10))
+            .unwrap()
+            .order_id,
+        3
+    );
+}
+
+#[test]
+fn test_modify_updates_composite() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 11,
+        trader_name: "Bob".to_string(),
+        buy: true,
+        volume: 100,
+    });
+
+    map.modify_by_order_id(&1, |o| o.timestamp = 20);
+    assert!(map.get_by_trader_ts(&("Alice".to_string(), 10)).is_none());
+    assert_eq!(
+        map.get_by_trader_ts(&("Alice".to_string(), 20))
+            .unwrap()
+            .order_id,
+        1
+    );
+
+    map.modify_by_trader_ts(&("Bob".to_string(), 11), |o| o.buy = false);
+    assert!(map
+        .get_by_trader_side(&("Bob".to_string(), true))
+        .is_empty());
+    assert_eq!(map.get_by_trader_side(&("Bob".to_string(), false)).len(), 1);
+}
+
+#[test]
+fn test_composite_iter_and_update() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 30,
+        trader_name: "Bob".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 20,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 3,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: false,
+        volume: 100,
+    });
+
+    let ids = map
+        .iter_by_trader_ts()
+        .map(|o| o.order_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![3, 2, 1]);
+
+    // Fields used by a composite index are not exposed to update_by_, only the unindexed `volume` is.
+    map.update_by_trader_ts(&("Bob".to_string(), 30), |volume| *volume = 0);
+    assert_eq!(map.get_by_order_id(&1).unwrap().volume, 0);
+}
+
+#[test]
+fn test_composite_keys_and_groups() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        timestamp: 10,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        timestamp: 11,
+        trader_name: "Alice".to_string(),
+        buy: true,
+        volume: 200,
+    });
+    map.insert(Order {
+        order_id: 3,
+        timestamp: 12,
+        trader_name: "Bob".to_string(),
+        buy: false,
+        volume: 300,
+    });
+
+    // Keys are yielded as the tuple the accessors take.
+    let mut keys = map.keys_by_trader_side().cloned().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![("Alice".to_string(), true), ("Bob".to_string(), false)]
+    );
+
+    let mut groups = map
+        .groups_by_trader_side()
+        .map(|(key, orders)| (key.clone(), orders.map(|o| o.order_id).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    groups.sort();
+    assert_eq!(
+        groups,
+        vec![
+            (("Alice".to_string(), true), vec![1, 2]),
+            (("Bob".to_string(), false), vec![3]),
+        ]
+    );
+
+    // Removing by another index finds the composite key without cloning the fields.
+    map.remove_by_order_id(&1);
+    assert_eq!(
+        map.get_by_trader_side(&("Alice".to_string(), true)).len(),
+        1
+    );
+    map.modify_by_order_id(&2, |o| o.buy = false);
+    assert!(map
+        .get_by_trader_side(&("Alice".to_string(), true))
+        .is_empty());
+    assert_eq!(
+        map.get_by_trader_side(&("Alice".to_string(), false)).len(),
+        1
+    );
+}
+
+#[test]
+fn test_composite_vacant_entry_sets_fields() {
+    let mut map = MultiIndexOrderMap::default();
+
+    match map.entry_by_trader_ts(("Alice".to_string(), 10)) {
+        MultiIndexOrderMapTraderTsEntry::Vacant(entry) => {
+            let inserted = entry
+                .insert(Order {
+                    order_id: 1,
+                    timestamp: 0,
+                    trader_name: String::new(),
+                    buy: true,
+                    volume: 100,
+                })
+                .unwrap();
+            assert_eq!(inserted.trader_name, "Alice");
+            assert_eq!(inserted.timestamp, 10);
+        }
+        MultiIndexOrderMapTraderTsEntry::Occupied(_) => panic!("Expected a vacant entry"),
+    }
+    assert_eq!(map.get_by_trader_name("Alice").len(), 1);
+    assert!(map.get_by_trader_ts(&("Alice".to_string(), 10)).is_some());
+    assert_eq!(
+        map.get_by_trader_side(&("Alice".to_string(), true)).len(),
+        1
+    );
+}
@@ -0,0 +1,106 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+}
+
+#[test]
+fn test_unique_counts() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name, price) in [
+        (1, 100, "alice", 10),
+        (2, 200, "bob", 20),
+        (3, 300, "alice", 10),
+        (4, 400, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    assert_eq!(map.count_by_order_id(&1), 1);
+    assert_eq!(map.count_by_order_id(&5), 0);
+    assert!(map.contains_order_id(&4));
+    assert!(!map.contains_order_id(&5));
+    assert_eq!(map.distinct_count_order_id(), 4);
+
+    assert_eq!(map.count_by_timestamp(&200), 1);
+    assert!(map.contains_timestamp(&300));
+    assert!(!map.contains_timestamp(&301));
+    assert_eq!(map.distinct_count_timestamp(), 4);
+}
+
+#[test]
+fn test_non_unique_counts() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name, price) in [
+        (1, 100, "alice", 10),
+        (2, 200, "bob", 20),
+        (3, 300, "alice", 10),
+        (4, 400, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    // Keys may be looked up by a borrowed form, as with get_by_.
+    assert_eq!(map.count_by_trader_name("alice"), 3);
+    assert_eq!(map.count_by_trader_name("carol"), 0);
+    assert!(map.contains_trader_name("bob"));
+    assert!(!map.contains_trader_name("carol"));
+    assert_eq!(map.distinct_count_trader_name(), 2);
+
+    assert_eq!(map.count_by_price(&10), 2);
+    assert_eq!(map.count_by_price(&15), 0);
+    assert!(map.contains_price(&30));
+    assert_eq!(map.distinct_count_price(), 3);
+}
+
+#[test]
+fn test_counts_after_modify_and_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name, price) in [
+        (1, 100, "alice", 10),
+        (2, 200, "bob", 20),
+        (3, 300, "alice", 10),
+        (4, 400, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    map.modify_by_order_id(&2, |e| e.trader_name = "alice".to_string());
+    assert_eq!(map.count_by_trader_name("alice"), 4);
+    assert!(!map.contains_trader_name("bob"));
+    assert_eq!(map.distinct_count_trader_name(), 1);
+
+    map.remove_by_price(&10);
+    assert_eq!(map.count_by_trader_name("alice"), 2);
+    assert!(!map.contains_order_id(&1));
+    assert!(!map.contains_price(&10));
+    assert_eq!(map.distinct_count_price(), 2);
+
+    map.clear();
+    assert_eq!(map.count_by_trader_name("alice"), 0);
+    assert_eq!(map.distinct_count_timestamp(), 0);
+}
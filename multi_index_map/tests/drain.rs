@@ -0,0 +1,170 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+    #[multi_index(hashed_non_unique, each)]
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_drain() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+
+    let mut drained = map.drain().map(|e| e.order_id).collect::<Vec<_>>();
+    drained.sort();
+    assert_eq!(drained, vec![1, 2, 3, 4]);
+    assert!(map.is_empty());
+    assert!(map.get_by_order_id(&1).is_none());
+    assert_eq!(map.iter_by_timestamp().count(), 0);
+    assert!(map.get_by_tags("a").is_empty());
+
+    // Every element is removed even if the iterator is not consumed.
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+    drop(map.drain());
+    assert!(map.is_empty());
+    assert_eq!(map.iter_by_price().count(), 0);
+}
+
+#[test]
+fn test_drain_by_index() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+    assert_eq!(
+        map.drain_by_timestamp()
+            .map(|e| e.order_id)
+            .collect::<Vec<_>>(),
+        vec![2, 4, 1, 3]
+    );
+    assert!(map.is_empty());
+    assert!(map.get_by_price(&20).is_empty());
+
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+    assert_eq!(
+        map.drain_by_price().map(|e| e.order_id).collect::<Vec<_>>(),
+        vec![2, 1, 3, 4]
+    );
+    assert!(map.is_empty());
+
+    // Each element is drained once, and elements with no key in the index remain.
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+    let mut drained = map.drain_by_tags().map(|e| e.order_id).collect::<Vec<_>>();
+    drained.sort();
+    assert_eq!(drained, vec![1, 2, 4]);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_timestamp(&400).unwrap().order_id, 3);
+}
+
+#[test]
+fn test_drain_range_by_index() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, price, tags) in [
+        (1, 300, 20, vec!["a", "b"]),
+        (2, 100, 10, vec!["b"]),
+        (3, 400, 20, vec![]),
+        (4, 200, 30, vec!["a", "a"]),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            price,
+            tags: tags.into_iter().map(String::from).collect(),
+        });
+    }
+
+    assert_eq!(
+        map.drain_range_by_timestamp(150..350)
+            .map(|e| e.order_id)
+            .collect::<Vec<_>>(),
+        vec![4, 1]
+    );
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_order_id(&4).is_none());
+    assert!(map.get_by_tags("a").is_empty());
+    assert_eq!(map.get_by_tags("b").len(), 1);
+
+    assert_eq!(
+        map.drain_range_by_price(..=10)
+            .map(|e| e.order_id)
+            .collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert_eq!(
+        map.drain_range_by_price(50..)
+            .map(|e| e.order_id)
+            .collect::<Vec<_>>(),
+        Vec::<u32>::new()
+    );
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_price(&20)[0].order_id, 3);
+}
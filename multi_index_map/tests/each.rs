@@ -0,0 +1,122 @@
+use multi_index_map::MultiIndexMap;
+use std::collections::BTreeSet;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(hashed_non_unique, each)]
+    tags: Vec<String>,
+    #[multi_index(ordered_non_unique, each)]
+    levels: BTreeSet<u32>,
+}
+
+#[test]
+fn test_each_item_is_a_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        tags: vec!["a".to_string(), "b".to_string()],
+        levels: BTreeSet::from([1, 2]),
+    });
+    map.insert(TestElement {
+        id: 2,
+        tags: vec!["b".to_string(), "c".to_string(), "b".to_string()],
+        levels: BTreeSet::from([2]),
+    });
+    map.insert(TestElement {
+        id: 3,
+        tags: Vec::new(),
+        levels: BTreeSet::new(),
+    });
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+
+    assert_eq!(ids(map.get_by_tags("a")), vec![1]);
+    assert_eq!(ids(map.get_by_tags("b")), vec![1, 2]);
+    assert_eq!(ids(map.get_by_tags("c")), vec![2]);
+    assert!(map.get_by_tags("d").is_empty());
+    assert_eq!(ids(map.get_by_levels(&2)), vec![1, 2]);
+
+    // Elements are visited once for each of their keys.
+    assert_eq!(map.iter_by_tags().count(), 4);
+    assert_eq!(
+        map.range_by_levels(2..).map(|e| e.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+}
+
+#[test]
+fn test_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        tags: vec!["a".to_string(), "b".to_string()],
+        levels: BTreeSet::from([1]),
+    });
+    map.insert(TestElement {
+        id: 2,
+        tags: vec!["b".to_string(), "c".to_string(), "b".to_string()],
+        levels: BTreeSet::from([1, 2]),
+    });
+    map.insert(TestElement {
+        id: 3,
+        tags: vec!["c".to_string()],
+        levels: BTreeSet::from([3]),
+    });
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+
+    assert_eq!(map.remove_by_id(&2).unwrap().id, 2);
+    assert_eq!(ids(map.get_by_tags("b")), vec![1]);
+    assert_eq!(ids(map.get_by_tags("c")), vec![3]);
+    assert!(map.get_by_levels(&2).is_empty());
+
+    let removed = map.remove_by_tags(&"b".to_string());
+    assert_eq!(removed.len(), 1);
+    assert!(map.get_by_tags("a").is_empty());
+    assert!(map.get_by_levels(&1).is_empty());
+    assert_eq!(map.iter_by_tags().count(), 1);
+
+    map.remove_by_levels(&3);
+    assert!(map.is_empty());
+    assert_eq!(map.iter_by_tags().count(), 0);
+}
+
+#[test]
+fn test_modify_diffs_items() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        tags: vec!["a".to_string(), "b".to_string()],
+        levels: BTreeSet::from([1, 2]),
+    });
+    map.insert(TestElement {
+        id: 2,
+        tags: vec!["b".to_string()],
+        levels: BTreeSet::from([2]),
+    });
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+
+    map.modify_by_id(&1, |e| {
+        e.tags = vec!["b".to_string(), "c".to_string(), "c".to_string()];
+        e.levels.remove(&1);
+        e.levels.insert(3);
+    });
+    assert!(map.get_by_tags("a").is_empty());
+    assert_eq!(ids(map.get_by_tags("b")), vec![1, 2]);
+    assert_eq!(ids(map.get_by_tags("c")), vec![1]);
+    assert!(map.get_by_levels(&1).is_empty());
+    assert_eq!(ids(map.get_by_levels(&2)), vec![1, 2]);
+    assert_eq!(ids(map.get_by_levels(&3)), vec![1]);
+
+    // Modifying through an each index updates every element holding the key.
+    map.modify_by_tags(&"b".to_string(), |e| e.tags.clear());
+    assert!(map.get_by_tags("b").is_empty());
+    assert!(map.get_by_tags("c").is_empty());
+    assert_eq!(map.iter_by_tags().count(), 0);
+    assert_eq!(map.len(), 2);
+
+    map.modify_by_levels(&2, |e| e.tags.push(format!("t{}", e.id)));
+    assert_eq!(ids(map.get_by_tags("t1")), vec![1]);
+    assert_eq!(ids(map.get_by_tags("t2")), vec![2]);
+}
@@ -0,0 +1,144 @@
+use multi_index_map::{ElementId, MultiIndexMap};
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+    note: String,
+}
+
+#[test]
+fn test_insert_returns_id() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        trader_name: "alice".to_string(),
+        price: 10,
+        note: String::new(),
+    });
+    let b = map
+        .try_insert(TestElement {
+            trader_name: "alice".to_string(),
+            price: 10,
+            note: String::new(),
+        })
+        .unwrap();
+    assert_ne!(a, b);
+
+    assert_eq!(
+        map.get_by_element_id(a),
+        Some(&TestElement {
+            trader_name: "alice".to_string(),
+            price: 10,
+            note: String::new(),
+        })
+    );
+    assert!(map.contains_element_id(b));
+
+    // The id is the position yielded by iterating over the backing storage.
+    let positions = map.iter().map(|(idx, _)| idx).collect::<Vec<_>>();
+    assert_eq!(positions, vec![a.index(), b.index()]);
+}
+
+#[test]
+fn test_modify_and_update_by_id() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        trader_name: "alice".to_string(),
+        price: 10,
+        note: String::new(),
+    });
+    let b = map.insert(TestElement {
+        trader_name: "bob".to_string(),
+        price: 10,
+        note: String::new(),
+    });
+
+    let updated = map
+        .update_by_element_id(a, |note| *note = "updated".to_string())
+        .unwrap();
+    assert_eq!(updated.note, "updated");
+
+    let modified = map
+        .modify_by_element_id(b, |e| {
+            e.trader_name = "alice".to_string();
+            e.price = 20;
+        })
+        .unwrap();
+    assert_eq!(modified.price, 20);
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert!(map.get_by_trader_name("bob").is_empty());
+    assert_eq!(map.get_by_price(&10).len(), 1);
+    assert_eq!(map.get_by_price(&20).len(), 1);
+}
+
+#[test]
+fn test_remove_by_id() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        trader_name: "alice".to_string(),
+        price: 10,
+        note: String::new(),
+    });
+    let b = map.insert(TestElement {
+        trader_name: "bob".to_string(),
+        price: 20,
+        note: String::new(),
+    });
+
+    assert_eq!(
+        map.remove_by_element_id(a),
+        Some(TestElement {
+            trader_name: "alice".to_string(),
+            price: 10,
+            note: String::new(),
+        })
+    );
+    assert_eq!(map.remove_by_element_id(a), None);
+    assert!(!map.contains_element_id(a));
+    assert!(map.get_by_element_id(a).is_none());
+    assert!(map.modify_by_element_id(a, |e| e.price = 0).is_none());
+    assert!(map.get_by_trader_name("alice").is_empty());
+    assert!(map.get_by_price(&10).is_empty());
+
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_element_id(b).unwrap().trader_name, "bob");
+    let unknown: ElementId = map.insert(TestElement {
+        trader_name: "carol".to_string(),
+        price: 30,
+        note: String::new(),
+    });
+    map.clear();
+    assert!(!map.contains_element_id(unknown));
+}
+
+#[test]
+fn test_iter_with_ids() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        trader_name: "alice".to_string(),
+        price: 10,
+        note: String::new(),
+    });
+    let b = map.insert(TestElement {
+        trader_name: "bob".to_string(),
+        price: 20,
+        note: String::new(),
+    });
+
+    let ids = map
+        .iter_with_ids()
+        .map(|(id, elem)| (id, elem.trader_name.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![(a, "alice".to_string()), (b, "bob".to_string())]);
+
+    // The ids yielded are usable with the other methods taking an ElementId.
+    let (id, _) = map
+        .iter_with_ids()
+        .find(|(_, elem)| elem.price == 20)
+        .unwrap();
+    map.modify_by_element_id(id, |e| e.price = 25);
+    assert_eq!(map.get_by_price(&25).len(), 1);
+}
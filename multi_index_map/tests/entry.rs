@@ -0,0 +1,164 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    quantity: u32,
+}
+
+#[test]
+fn test_occupied_entry() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        quantity: 10,
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        quantity: 20,
+    });
+
+    match map.entry_by_order_id(1) {
+        MultiIndexTestElementMapOrderIdEntry::Occupied(entry) => {
+            assert_eq!(entry.get().timestamp, 100);
+            let updated = entry.update(|quantity| *quantity += 5);
+            assert_eq!(updated.quantity, 15);
+        }
+        MultiIndexTestElementMapOrderIdEntry::Vacant(_) => panic!("Expected an occupied entry"),
+    }
+
+    match map.entry_by_timestamp(200) {
+        MultiIndexTestElementMapTimestampEntry::Occupied(entry) => {
+            let modified = entry.modify(|e| {
+                e.timestamp = 300;
+                e.trader_name = "alice".to_string();
+            });
+            assert_eq!(modified.order_id, 2);
+        }
+        MultiIndexTestElementMapTimestampEntry::Vacant(_) => panic!("Expected an occupied entry"),
+    }
+    assert!(map.get_by_timestamp(&200).is_none());
+    assert_eq!(map.get_by_timestamp(&300).unwrap().order_id, 2);
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+
+    match map.entry_by_order_id(1) {
+        MultiIndexTestElementMapOrderIdEntry::Occupied(entry) => {
+            let removed = entry.remove();
+            assert_eq!(removed.order_id, 1);
+            assert_eq!(removed.quantity, 15);
+        }
+        MultiIndexTestElementMapOrderIdEntry::Vacant(_) => panic!("Expected an occupied entry"),
+    }
+    assert_eq!(map.len(), 1);
+    assert!(map.get_by_timestamp(&100).is_none());
+    assert_eq!(map.get_by_trader_name("alice").len(), 1);
+}
+
+#[test]
+fn test_vacant_entry() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        quantity: 10,
+    });
+
+    match map.entry_by_order_id(3) {
+        MultiIndexTestElementMapOrderIdEntry::Vacant(entry) => {
+            assert_eq!(*entry.key(), 3);
+            let inserted = entry
+                .insert(TestElement {
+                    order_id: 3,
+                    timestamp: 300,
+                    trader_name: "carol".to_string(),
+                    quantity: 30,
+                })
+                .unwrap();
+            assert_eq!(inserted.quantity, 30);
+        }
+        MultiIndexTestElementMapOrderIdEntry::Occupied(_) => panic!("Expected a vacant entry"),
+    }
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_timestamp(&300).unwrap().order_id, 3);
+
+    // The other unique indexes are still checked when inserting through a vacant entry.
+    match map.entry_by_order_id(4) {
+        MultiIndexTestElementMapOrderIdEntry::Vacant(entry) => {
+            let rejected = entry
+                .insert(TestElement {
+                    order_id: 4,
+                    timestamp: 100,
+                    trader_name: "dave".to_string(),
+                    quantity: 40,
+                })
+                .unwrap_err();
+            assert_eq!(rejected.element.order_id, 4);
+        }
+        MultiIndexTestElementMapOrderIdEntry::Occupied(_) => panic!("Expected a vacant entry"),
+    }
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_order_id(&4).is_none());
+}
+
+#[test]
+fn test_vacant_entry_sets_key() {
+    let mut map = MultiIndexTestElementMap::default();
+
+    // The element is inserted under the key of the entry, whatever key it was given.
+    if let MultiIndexTestElementMapOrderIdEntry::Vacant(entry) = map.entry_by_order_id(3) {
+        let inserted = entry
+            .insert(TestElement {
+                order_id: 4,
+                timestamp: 400,
+                trader_name: "dave".to_string(),
+                quantity: 40,
+            })
+            .unwrap();
+        assert_eq!(inserted.order_id, 3);
+    }
+    assert_eq!(map.get_by_order_id(&3).unwrap().timestamp, 400);
+    assert!(map.get_by_order_id(&4).is_none());
+}
+
+#[test]
+fn test_upsert() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        quantity: 10,
+    });
+
+    for (order_id, quantity) in [(1, 5), (3, 7), (3, 1)] {
+        match map.entry_by_order_id(order_id) {
+            MultiIndexTestElementMapOrderIdEntry::Occupied(entry) => {
+                entry.update(|q| *q += quantity);
+            }
+            MultiIndexTestElementMapOrderIdEntry::Vacant(entry) => {
+                let order_id = entry.into_key();
+                map.insert(TestElement {
+                    order_id,
+                    timestamp: order_id as u64 * 100,
+                    trader_name: "carol".to_string(),
+                    quantity,
+                });
+            }
+        }
+    }
+
+    assert_eq!(map.get_by_order_id(&1).unwrap().quantity, 15);
+    assert_eq!(map.get_by_order_id(&3).unwrap().quantity, 8);
+    assert_eq!(map.len(), 2);
+}
@@ -0,0 +1,149 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+#[multi_index_generational]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+    note: String,
+}
+
+#[test]
+fn test_handle_access() {
+    let mut map = MultiIndexTestElementMap::default();
+    let handle = map.insert_with_handle(TestElement {
+        order_id: 1,
+        price: 10,
+        note: String::new(),
+    });
+    assert!(map.contains_handle(handle));
+    assert_eq!(map.get_by_handle(handle).unwrap().order_id, 1);
+    assert_eq!(
+        map.get_by_element_id(handle.element_id()).unwrap().order_id,
+        1
+    );
+
+    map.update_by_handle(handle, |note| *note = "updated".to_string());
+    assert_eq!(map.get_by_handle(handle).unwrap().note, "updated");
+
+    map.modify_by_handle(handle, |e| e.price = 20);
+    assert_eq!(map.get_by_price(&20).len(), 1);
+    assert!(map.get_by_price(&10).is_empty());
+
+    assert_eq!(map.remove_by_handle(handle).unwrap().order_id, 1);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_try_insert_with_handle() {
+    let mut map = MultiIndexTestElementMap::default();
+    let handle = map
+        .try_insert_with_handle(TestElement {
+            order_id: 1,
+            price: 10,
+            note: String::new(),
+        })
+        .unwrap();
+    assert!(map.contains_handle(handle));
+
+    let rejected = map
+        .try_insert_with_handle(TestElement {
+            order_id: 1,
+            price: 20,
+            note: String::new(),
+        })
+        .unwrap_err();
+    assert_eq!(rejected.element.price, 20);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_iter_with_handles() {
+    let mut map = MultiIndexTestElementMap::default();
+    for order_id in 1..=3 {
+        map.insert(TestElement {
+            order_id,
+            price: 10,
+            note: String::new(),
+        });
+    }
+    map.remove_by_order_id(&2);
+
+    let handles = map
+        .iter_with_handles()
+        .map(|(handle, elem)| (elem.order_id, handle))
+        .collect::<Vec<_>>();
+    assert_eq!(handles.len(), 2);
+    for (order_id, handle) in handles {
+        assert_eq!(map.get_by_handle(handle).unwrap().order_id, order_id);
+        assert_eq!(map.remove_by_handle(handle).unwrap().order_id, order_id);
+        assert!(!map.contains_handle(handle));
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_stale_handle_after_reuse() {
+    let mut map = MultiIndexTestElementMap::default();
+    let stale = map.insert_with_handle(TestElement {
+        order_id: 1,
+        price: 10,
+        note: String::new(),
+    });
+    map.remove_by_order_id(&1);
+    assert!(!map.contains_handle(stale));
+
+    // The new element reuses the position, but not the generation.
+    let fresh = map.insert_with_handle(TestElement {
+        order_id: 2,
+        price: 20,
+        note: String::new(),
+    });
+    assert_eq!(fresh.element_id(), stale.element_id());
+    assert_ne!(fresh, stale);
+
+    assert_eq!(map.get_by_handle(stale), None);
+    assert!(map.update_by_handle(stale, |_| {}).is_none());
+    assert!(map.modify_by_handle(stale, |e| e.price = 30).is_none());
+    assert!(map.remove_by_handle(stale).is_none());
+    assert_eq!(map.get_by_handle(fresh).unwrap().order_id, 2);
+    assert_eq!(map.get_by_price(&20).len(), 1);
+}
+
+#[test]
+fn test_stale_handle_after_clear() {
+    let mut map = MultiIndexTestElementMap::default();
+    let handle = map.insert_with_handle(TestElement {
+        order_id: 1,
+        price: 10,
+        note: String::new(),
+    });
+    map.clear();
+    map.insert(TestElement {
+        order_id: 1,
+        price: 10,
+        note: String::new(),
+    });
+    assert!(!map.contains_handle(handle));
+
+    let handle = map.insert_with_handle(TestElement {
+        order_id: 2,
+        price: 10,
+        note: String::new(),
+    });
+    map.drain().for_each(drop);
+    map.insert(TestElement {
+        order_id: 3,
+        price: 10,
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 4,
+        price: 10,
+        note: String::new(),
+    });
+    assert_eq!(map.get_by_handle(handle), None);
+}
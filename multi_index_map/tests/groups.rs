@@ -0,0 +1,109 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+}
+
+#[test]
+fn test_keys() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, trader_name, price) in [
+        (1, "alice", 30),
+        (2, "bob", 10),
+        (3, "alice", 20),
+        (4, "carol", 10),
+        (5, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    let mut trader_names = map.keys_by_trader_name().collect::<Vec<_>>();
+    trader_names.sort();
+    assert_eq!(trader_names, vec!["alice", "bob", "carol"]);
+
+    // Ordered indexes yield their keys in order.
+    assert_eq!(
+        map.keys_by_price().copied().collect::<Vec<_>>(),
+        vec![10, 20, 30]
+    );
+}
+
+#[test]
+fn test_groups() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, trader_name, price) in [
+        (1, "alice", 30),
+        (2, "bob", 10),
+        (3, "alice", 20),
+        (4, "carol", 10),
+        (5, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    let mut totals = map
+        .groups_by_trader_name()
+        .map(|(name, orders)| (name.as_str(), orders.map(|o| o.price).sum::<u32>()))
+        .collect::<Vec<_>>();
+    totals.sort();
+    assert_eq!(totals, vec![("alice", 80), ("bob", 10), ("carol", 10)]);
+
+    // Within a group, elements are in order of their position in the backing storage.
+    let groups = map
+        .groups_by_price()
+        .map(|(price, orders)| (*price, orders.map(|o| o.order_id).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        vec![(10, vec![2, 4]), (20, vec![3]), (30, vec![1, 5])]
+    );
+}
+
+#[test]
+fn test_groups_after_modify_and_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, trader_name, price) in [
+        (1, "alice", 30),
+        (2, "bob", 10),
+        (3, "alice", 20),
+        (4, "carol", 10),
+        (5, "alice", 30),
+    ] {
+        map.insert(TestElement {
+            order_id,
+            trader_name: trader_name.to_string(),
+            price,
+        });
+    }
+
+    map.modify_by_order_id(&2, |o| o.trader_name = "carol".to_string());
+    map.remove_by_order_id(&3);
+
+    let groups = map
+        .groups_by_trader_name()
+        .map(|(name, orders)| (name.clone(), orders.count()))
+        .collect::<std::collections::BTreeMap<_, _>>();
+    assert_eq!(groups.get("alice"), Some(&2));
+    assert_eq!(groups.get("bob"), None);
+    assert_eq!(groups.get("carol"), Some(&2));
+    assert_eq!(map.keys_by_price().count(), 2);
+
+    map.clear();
+    assert_eq!(map.keys_by_trader_name().count(), 0);
+    assert_eq!(map.groups_by_price().count(), 0);
+}
@@ -0,0 +1,130 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+}
+
+#[test]
+fn test_insert_without_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    assert!(map
+        .insert_or_replace(TestElement {
+            order_id: 1,
+            timestamp: 100,
+            trader_name: "alice".to_string(),
+        })
+        .is_empty());
+    assert!(map
+        .insert_or_replace(TestElement {
+            order_id: 2,
+            timestamp: 200,
+            trader_name: "alice".to_string(),
+        })
+        .is_empty());
+
+    // Non-unique indexes never conflict.
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+}
+
+#[test]
+fn test_replace_single_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+    });
+
+    let replaced = map.insert_or_replace(TestElement {
+        order_id: 1,
+        timestamp: 300,
+        trader_name: "carol".to_string(),
+    });
+    assert_eq!(
+        replaced,
+        vec![TestElement {
+            order_id: 1,
+            timestamp: 100,
+            trader_name: "alice".to_string(),
+        }]
+    );
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_order_id(&1).unwrap().timestamp, 300);
+    assert!(map.get_by_timestamp(&100).is_none());
+    assert!(map.get_by_trader_name("alice").is_empty());
+
+    // An element conflicting on several indexes is only replaced once.
+    let replaced = map.insert_or_replace(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "dave".to_string(),
+    });
+    assert_eq!(
+        replaced,
+        vec![TestElement {
+            order_id: 2,
+            timestamp: 200,
+            trader_name: "bob".to_string(),
+        }]
+    );
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_replace_several_conflicts() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+    });
+    map.insert(TestElement {
+        order_id: 3,
+        timestamp: 300,
+        trader_name: "carol".to_string(),
+    });
+
+    // Conflicts with element 1 by order_id, and element 2 by timestamp.
+    let replaced = map.insert_or_replace(TestElement {
+        order_id: 1,
+        timestamp: 200,
+        trader_name: "dave".to_string(),
+    });
+    assert_eq!(
+        replaced,
+        vec![
+            TestElement {
+                order_id: 1,
+                timestamp: 100,
+                trader_name: "alice".to_string(),
+            },
+            TestElement {
+                order_id: 2,
+                timestamp: 200,
+                trader_name: "bob".to_string(),
+            }
+        ]
+    );
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_timestamp(&200).unwrap().trader_name, "dave");
+    assert!(map.get_by_order_id(&2).is_none());
+    assert_eq!(map.get_by_order_id(&3).unwrap().timestamp, 300);
+}
@@ -0,0 +1,155 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+#[multi_index_interval(validity(fields(valid_from, valid_to)))]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    valid_from: u64,
+    valid_to: u64,
+}
+
+#[test]
+fn test_containing_and_overlapping() {
+    let mut map = MultiIndexTestElementMap::default();
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+    map.insert(TestElement {
+        id: 1,
+        valid_from: 0,
+        valid_to: 10,
+    });
+    map.insert(TestElement {
+        id: 2,
+        valid_from: 5,
+        valid_to: 15,
+    });
+    map.insert(TestElement {
+        id: 3,
+        valid_from: 10,
+        valid_to: 20,
+    });
+    map.insert(TestElement {
+        id: 4,
+        valid_from: 30,
+        valid_to: 40,
+    });
+    map.insert(TestElement {
+        id: 5,
+        valid_from: 8,
+        valid_to: 8,
+    });
+
+    // Intervals are half-open, and returned in order of their start.
+    assert_eq!(ids(map.containing_validity(&0)), vec![1]);
+    assert_eq!(ids(map.containing_validity(&9)), vec![1, 2]);
+    assert_eq!(ids(map.containing_validity(&10)), vec![2, 3]);
+    assert_eq!(ids(map.containing_validity(&20)), Vec::<u32>::new());
+    assert_eq!(ids(map.containing_validity(&8)), vec![1, 2]);
+
+    assert_eq!(ids(map.overlapping_validity(12..35)), vec![2, 3, 4]);
+    assert_eq!(ids(map.overlapping_validity(20..30)), Vec::<u32>::new());
+    assert_eq!(ids(map.overlapping_validity(0..100)), vec![1, 2, 3, 4]);
+    assert_eq!(ids(map.overlapping_validity(5..5)), Vec::<u32>::new());
+}
+
+#[test]
+fn test_modify_and_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+    map.insert(TestElement {
+        id: 1,
+        valid_from: 0,
+        valid_to: 10,
+    });
+    map.insert(TestElement {
+        id: 2,
+        valid_from: 5,
+        valid_to: 15,
+    });
+
+    map.modify_by_id(&1, |e| e.valid_to = 3);
+    assert_eq!(ids(map.containing_validity(&5)), vec![2]);
+    assert_eq!(ids(map.containing_validity(&2)), vec![1]);
+
+    map.modify_by_id(&2, |e| {
+        e.valid_from = 100;
+        e.valid_to = 200;
+    });
+    assert_eq!(ids(map.overlapping_validity(0..50)), vec![1]);
+    assert_eq!(ids(map.containing_validity(&150)), vec![2]);
+
+    map.remove_by_id(&1);
+    assert!(map.containing_validity(&2).is_empty());
+    map.insert(TestElement {
+        id: 3,
+        valid_from: 0,
+        valid_to: 5,
+    });
+    assert_eq!(ids(map.containing_validity(&2)), vec![3]);
+    assert_eq!(ids(map.clone().overlapping_validity(0..1000)), vec![3, 2]);
+
+    map.clear();
+    assert!(map.overlapping_validity(0..1000).is_empty());
+}
+
+#[test]
+fn test_matches_full_scan() {
+    let mut map = MultiIndexTestElementMap::default();
+    let ids = |elems: Vec<&TestElement>| elems.iter().map(|e| e.id).collect::<Vec<_>>();
+    let mut state = 12345u64;
+    let mut next = move |bound: u64| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % bound
+    };
+
+    for round in 0..2000 {
+        let id = next(300) as u32;
+        match next(3) {
+            0 => {
+                map.remove_by_id(&id);
+            }
+            1 => {
+                let (start, len) = (next(1000), next(100));
+                map.modify_by_id(&id, |e| {
+                    e.valid_from = start;
+                    e.valid_to = start + len;
+                });
+            }
+            _ => {
+                let (start, len) = (next(1000), next(100));
+                let _ = map.try_insert(TestElement {
+                    id,
+                    valid_from: start,
+                    valid_to: start + len,
+                });
+            }
+        }
+
+        if round % 50 == 0 {
+            let (start, end) = (next(1100), next(1100));
+            let mut expected = map
+                .iter()
+                .filter(|(_, e)| e.valid_from.max(start) < e.valid_to.min(end))
+                .map(|(_, e)| e.id)
+                .collect::<Vec<_>>();
+            let mut found = ids(map.overlapping_validity(start..end));
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected);
+
+            let point = next(1100);
+            let mut expected = map
+                .iter()
+                .filter(|(_, e)| e.valid_from <= point && point < e.valid_to)
+                .map(|(_, e)| e.id)
+                .collect::<Vec<_>>();
+            let mut found = ids(map.containing_validity(&point));
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected);
+        }
+    }
+}
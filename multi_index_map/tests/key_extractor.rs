@@ -0,0 +1,134 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+#[multi_index_key(symbol_prefix(hashed_non_unique, fn = "Order::symbol_prefix", ty = "String"))]
+#[multi_index_key(lower_trader(ordered_unique, fn = "Order::lower_trader", ty = "String"))]
+struct Order {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    symbol: String,
+    trader_name: String,
+    volume: u64,
+}
+
+impl Order {
+    fn symbol_prefix(&self) -> String {
+        self.symbol.chars().take(2).collect()
+    }
+
+    fn lower_trader(&self) -> String {
+        self.trader_name.to_lowercase()
+    }
+}
+
+#[test]
+fn test_extracted_key_insert_and_get() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        symbol: "AAPL".to_string(),
+        trader_name: "Alice".to_string(),
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        symbol: "AAL".to_string(),
+        trader_name: "Bob".to_string(),
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 3,
+        symbol: "MSFT".to_string(),
+        trader_name: "Carol".to_string(),
+        volume: 100,
+    });
+
+    assert_eq!(map.get_by_symbol_prefix("AA").len(), 2);
+    assert_eq!(map.get_by_symbol_prefix("MS").len(), 1);
+    assert!(map.get_by_symbol_prefix("GO").is_empty());
+
+    assert_eq!(map.get_by_lower_trader("bob").unwrap().order_id, 2);
+
+    // Extracted keys are still subject to uniqueness constraints.
+    let rejected = map
+        .try_insert(Order {
+            order_id: 4,
+            symbol: "GOOG".to_string(),
+            trader_name: "ALICE".to_string(),
+            volume: 100,
+        })
+        .unwrap_err();
+    assert_eq!(rejected.element.order_id, 4);
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn test_extracted_key_remove() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        symbol: "AAPL".to_string(),
+        trader_name: "Alice".to_string(),
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        symbol: "AAL".to_string(),
+        trader_name: "Bob".to_string(),
+        volume: 100,
+    });
+
+    let removed = map.remove_by_symbol_prefix(&"AA".to_string());
+    assert_eq!(removed.len(), 2);
+    assert!(map.is_empty());
+    assert!(map.get_by_lower_trader("alice").is_none());
+
+    map.insert(Order {
+        order_id: 3,
+        symbol: "AAPL".to_string(),
+        trader_name: "alice".to_string(),
+        volume: 100,
+    });
+    map.remove_by_order_id(&3);
+    assert!(map.get_by_symbol_prefix("AA").is_empty());
+    assert!(map.get_by_lower_trader("alice").is_none());
+}
+
+#[test]
+fn test_extracted_key_modify_and_iter() {
+    let mut map = MultiIndexOrderMap::default();
+    map.insert(Order {
+        order_id: 1,
+        symbol: "AAPL".to_string(),
+        trader_name: "Carol".to_string(),
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 2,
+        symbol: "MSFT".to_string(),
+        trader_name: "alice".to_string(),
+        volume: 100,
+    });
+    map.insert(Order {
+        order_id: 3,
+        symbol: "MSTR".to_string(),
+        trader_name: "Bob".to_string(),
+        volume: 100,
+    });
+
+    map.modify_by_order_id(&1, |o| o.symbol = "MSCI".to_string());
+    assert!(map.get_by_symbol_prefix("AA").is_empty());
+    assert_eq!(map.get_by_symbol_prefix("MS").len(), 3);
+
+    let traders = map
+        .iter_by_lower_trader()
+        .map(|o| o.trader_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(traders, vec!["alice", "Bob", "Carol"]);
+
+    // A key function may read any field, so no field can be updated in place, only through modify_by_.
+    map.update_by_lower_trader("bob", || ());
+    map.modify_by_lower_trader(&"bob".to_string(), |o| o.volume = 0);
+    assert_eq!(map.get_by_order_id(&3).unwrap().volume, 0);
+}
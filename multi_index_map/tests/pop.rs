@@ -0,0 +1,69 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_unique)]
+    priority: u32,
+    #[multi_index(ordered_non_unique)]
+    deadline: u64,
+}
+
+#[test]
+fn test_unique_pop() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (id, priority, deadline) in [(1, 30, 100), (2, 10, 200), (3, 20, 100), (4, 40, 300)] {
+        map.insert(TestElement {
+            id,
+            priority,
+            deadline,
+        });
+    }
+
+    assert_eq!(map.pop_first_by_priority().unwrap().id, 2);
+    assert_eq!(map.pop_last_by_priority().unwrap().id, 4);
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_id(&2).is_none());
+    assert!(map.get_by_deadline(&300).is_empty());
+    assert_eq!(map.get_by_deadline(&100).len(), 2);
+
+    assert_eq!(map.pop_first_by_priority().unwrap().id, 3);
+    assert_eq!(map.pop_first_by_priority().unwrap().id, 1);
+    assert_eq!(map.pop_first_by_priority(), None);
+    assert_eq!(map.pop_last_by_priority(), None);
+    assert_eq!(map.iter_by_deadline().count(), 0);
+}
+
+#[test]
+fn test_non_unique_pop() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (id, priority, deadline) in [(1, 30, 100), (2, 10, 200), (3, 20, 100), (4, 40, 300)] {
+        map.insert(TestElement {
+            id,
+            priority,
+            deadline,
+        });
+    }
+
+    let mut expired = map
+        .pop_first_by_deadline()
+        .into_iter()
+        .map(|e| e.id)
+        .collect::<Vec<_>>();
+    expired.sort();
+    assert_eq!(expired, vec![1, 3]);
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_priority(&30).is_none());
+    assert!(map.get_by_id(&3).is_none());
+
+    let last = map.pop_last_by_deadline();
+    assert_eq!(last.len(), 1);
+    assert_eq!(last[0].id, 4);
+
+    assert_eq!(map.pop_first_by_deadline()[0].id, 2);
+    assert!(map.pop_first_by_deadline().is_empty());
+    assert!(map.pop_last_by_deadline().is_empty());
+    assert!(map.is_empty());
+}
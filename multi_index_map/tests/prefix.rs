@@ -0,0 +1,133 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(ordered_unique)]
+    symbol: String,
+    #[multi_index(ordered_non_unique)]
+    venue: String,
+}
+
+#[test]
+fn test_unique_prefix() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (symbol, venue) in [
+        ("AAPL", "XNAS"),
+        ("AMD", "XNAS"),
+        ("AMZN", "XNAS"),
+        ("A", "XNYS"),
+        ("B", "XNYS"),
+        ("AB", "XLON"),
+        ("AZ\u{10FFFF}", "\u{10FFFF}"),
+        ("\u{10FFFF}\u{10FFFF}", "\u{10FFFF}\u{10FFFF}"),
+    ] {
+        map.insert(TestElement {
+            symbol: symbol.to_string(),
+            venue: venue.to_string(),
+        });
+    }
+
+    assert_eq!(
+        map.prefix_by_symbol("AM")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["AMD", "AMZN"]
+    );
+    assert_eq!(
+        map.prefix_by_symbol("A")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["A", "AAPL", "AB", "AMD", "AMZN", "AZ\u{10FFFF}"]
+    );
+    assert_eq!(
+        map.prefix_by_symbol("AMD")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["AMD"]
+    );
+    assert_eq!(map.prefix_by_symbol("AMDX").count(), 0);
+    assert_eq!(map.prefix_by_symbol("C").count(), 0);
+    assert_eq!(map.prefix_by_symbol("").count(), 8);
+    assert_eq!(
+        map.prefix_by_symbol("A")
+            .rev()
+            .take(2)
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["AZ\u{10FFFF}", "AMZN"]
+    );
+}
+
+#[test]
+fn test_prefix_of_max_chars() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (symbol, venue) in [
+        ("AAPL", "XNAS"),
+        ("AMD", "XNAS"),
+        ("AMZN", "XNAS"),
+        ("A", "XNYS"),
+        ("B", "XNYS"),
+        ("AB", "XLON"),
+        ("AZ\u{10FFFF}", "\u{10FFFF}"),
+        ("\u{10FFFF}\u{10FFFF}", "\u{10FFFF}\u{10FFFF}"),
+    ] {
+        map.insert(TestElement {
+            symbol: symbol.to_string(),
+            venue: venue.to_string(),
+        });
+    }
+
+    assert_eq!(
+        map.prefix_by_symbol("AZ")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["AZ\u{10FFFF}"]
+    );
+    assert_eq!(
+        map.prefix_by_symbol("\u{10FFFF}")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["\u{10FFFF}\u{10FFFF}"]
+    );
+    assert_eq!(map.prefix_by_venue("\u{10FFFF}").count(), 2);
+}
+
+#[test]
+fn test_non_unique_prefix() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (symbol, venue) in [
+        ("AAPL", "XNAS"),
+        ("AMD", "XNAS"),
+        ("AMZN", "XNAS"),
+        ("A", "XNYS"),
+        ("B", "XNYS"),
+        ("AB", "XLON"),
+        ("AZ\u{10FFFF}", "\u{10FFFF}"),
+        ("\u{10FFFF}\u{10FFFF}", "\u{10FFFF}\u{10FFFF}"),
+    ] {
+        map.insert(TestElement {
+            symbol: symbol.to_string(),
+            venue: venue.to_string(),
+        });
+    }
+
+    assert_eq!(map.prefix_by_venue("XN").count(), 5);
+    assert_eq!(
+        map.prefix_by_venue("XNY")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["A", "B"]
+    );
+
+    map.modify_by_symbol(&"B".to_string(), |e| e.venue = "XLON".to_string());
+    map.remove_by_symbol(&"AMD".to_string());
+    assert_eq!(map.prefix_by_venue("XN").count(), 3);
+    // Elements with equal keys are yielded in the order of their position in the backing storage.
+    assert_eq!(
+        map.prefix_by_venue("XL")
+            .map(|e| e.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["B", "AB"]
+    );
+}
@@ -0,0 +1,238 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+#[multi_index_random_access]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+}
+
+#[test]
+fn test_nth_and_position_of() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+        });
+    }
+
+    assert_eq!(map.nth(0).unwrap().field1, 0);
+    assert_eq!(map.nth(4).unwrap().field1, 4);
+    assert_eq!(map.nth(5), None);
+
+    for i in 0..5 {
+        assert_eq!(map.position_of_field1(&i), Some(i as usize));
+    }
+    assert_eq!(map.position_of_field1(&5), None);
+    assert_eq!(map.iter_random_access().len(), 5);
+}
+
+#[test]
+fn test_insert_at() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+        });
+    }
+
+    map.insert_at(
+        2,
+        TestElement {
+            field1: 10,
+            field2: 0,
+        },
+    );
+    map.insert_at(
+        0,
+        TestElement {
+            field1: 11,
+            field2: 0,
+        },
+    );
+    map.insert_at(
+        7,
+        TestElement {
+            field1: 12,
+            field2: 0,
+        },
+    );
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![11, 0, 1, 10, 2, 3, 4, 12]
+    );
+    assert_eq!(map.position_of_field1(&10), Some(3));
+    assert_eq!(map.position_of_field1(&4), Some(6));
+    assert_eq!(map.get_by_field2(&0).len(), 6);
+
+    // A rejected element leaves the positions unchanged.
+    let rejected = map
+        .try_insert_at(
+            1,
+            TestElement {
+                field1: 3,
+                field2: 0,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(rejected.element.field1, 3);
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![11, 0, 1, 10, 2, 3, 4, 12]
+    );
+}
+
+#[test]
+#[should_panic(expected = "insertion position (is 6) should be <= len (is 5)")]
+fn test_insert_at_out_of_bounds() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+        });
+    }
+    map.insert_at(
+        6,
+        TestElement {
+            field1: 10,
+            field2: 0,
+        },
+    );
+}
+
+#[test]
+fn test_remove_shifts_positions() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+        });
+    }
+
+    assert_eq!(map.remove_at(1).unwrap().field1, 1);
+    assert_eq!(map.remove_at(4), None);
+    assert!(map.get_by_field1(&1).is_none());
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![0, 2, 3, 4]
+    );
+    assert_eq!(map.position_of_field1(&3), Some(2));
+
+    map.remove_by_field2(&0);
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![3]
+    );
+    assert_eq!(map.position_of_field1(&3), Some(0));
+
+    // Slots freed in the backing storage are reused, but new elements are still appended.
+    map.insert(TestElement {
+        field1: 5,
+        field2: 1,
+    });
+    map.insert_at(
+        0,
+        TestElement {
+            field1: 6,
+            field2: 1,
+        },
+    );
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![6, 3, 5]
+    );
+    assert_eq!(
+        map.iter_random_access()
+            .rev()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![5, 3, 6]
+    );
+
+    map.clear();
+    assert_eq!(map.nth(0), None);
+    map.insert(TestElement {
+        field1: 7,
+        field2: 1,
+    });
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![7]
+    );
+}
+
+#[test]
+fn test_positions_match_vec_after_many_changes() {
+    let mut map = MultiIndexTestElementMap::default();
+    let mut expected: Vec<u32> = Vec::new();
+
+    // A fixed linear congruential generator, so the sequence of changes is the same on every run.
+    let mut state: u64 = 12345;
+    let mut next = |bound: usize| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as usize % bound
+    };
+
+    for i in 0..2000 {
+        if expected.is_empty() || next(3) != 0 {
+            let position = next(expected.len() + 1);
+            map.insert_at(
+                position,
+                TestElement {
+                    field1: i,
+                    field2: i % 7,
+                },
+            );
+            expected.insert(position, i);
+        } else {
+            let position = next(expected.len());
+            let removed = map.remove_at(position).unwrap();
+            assert_eq!(removed.field1, expected.remove(position));
+        }
+    }
+
+    // Removals through other indexes and retain remove from the middle of the order too.
+    map.remove_by_field2(&3);
+    map.retain(|e| e.field1 % 5 != 0);
+    expected.retain(|field1| field1 % 7 != 3 && field1 % 5 != 0);
+
+    assert_eq!(map.len(), expected.len());
+    assert_eq!(
+        map.iter_random_access()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        expected
+    );
+    assert_eq!(
+        map.iter_random_access()
+            .rev()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        expected.iter().rev().copied().collect::<Vec<_>>()
+    );
+    for (position, field1) in expected.iter().enumerate() {
+        assert_eq!(map.nth(position).unwrap().field1, *field1);
+        assert_eq!(map.position_of_field1(field1), Some(position));
+    }
+}
@@ -0,0 +1,106 @@
+use multi_index_map::MultiIndexMap;
+use std::ops::Bound;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(ordered_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: String,
+    #[multi_index(hashed_unique)]
+    field3: u32,
+}
+
+#[test]
+fn test_unique_range() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i * 10,
+            field2: format!("key{}", i / 3),
+            field3: i,
+        });
+    }
+
+    let elems = map.range_by_field1(20..50).collect::<Vec<_>>();
+    assert_eq!(elems.len(), 3);
+    assert_eq!(elems[0].field1, 20);
+    assert_eq!(elems[2].field1, 40);
+
+    let elems = map.range_by_field1(..=20).collect::<Vec<_>>();
+    assert_eq!(elems.len(), 3);
+
+    let elems = map.range_by_field1(85..).collect::<Vec<_>>();
+    assert_eq!(elems.len(), 1);
+    assert_eq!(elems[0].field1, 90);
+
+    assert_eq!(map.range_by_field1(..).count(), 10);
+    assert_eq!(map.range_by_field1(91..).count(), 0);
+}
+
+#[test]
+fn test_unique_range_rev() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i * 10,
+            field2: format!("key{}", i / 3),
+            field3: i,
+        });
+    }
+
+    let mut it = map.range_by_field1(30..=60);
+    assert_eq!(it.next_back().unwrap().field1, 60);
+    assert_eq!(it.next().unwrap().field1, 30);
+    assert_eq!(it.next_back().unwrap().field1, 50);
+    assert_eq!(it.next().unwrap().field1, 40);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
+
+#[test]
+fn test_non_unique_range() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i * 10,
+            field2: format!("key{}", i / 3),
+            field3: i,
+        });
+    }
+
+    let elems = map
+        .range_by_field2::<str, _>((Bound::Included("key1"), Bound::Unbounded))
+        .collect::<Vec<_>>();
+    assert_eq!(elems.len(), 7);
+    assert!(elems.iter().all(|e| e.field2.as_str() >= "key1"));
+
+    let elems = map
+        .range_by_field2("key1".to_string().."key3".to_string())
+        .rev()
+        .map(|e| e.field3)
+        .collect::<Vec<_>>();
+    assert_eq!(elems, vec![8, 7, 6, 5, 4, 3]);
+}
+
+#[test]
+fn test_range_after_modify_and_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i * 10,
+            field2: format!("key{}", i / 3),
+            field3: i,
+        });
+    }
+
+    map.modify_by_field3(&0, |e| e.field1 = 55);
+    map.remove_by_field1(&50);
+
+    let elems = map
+        .range_by_field1(40..70)
+        .map(|e| e.field1)
+        .collect::<Vec<_>>();
+    assert_eq!(elems, vec![40, 55, 60]);
+}
@@ -0,0 +1,102 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct Player {
+    #[multi_index(hashed_unique)]
+    name: String,
+    #[multi_index(ordered_unique, ranked)]
+    score: u32,
+    #[multi_index(ordered_non_unique, ranked)]
+    level: u8,
+}
+
+#[test]
+fn test_rank_and_nth() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, level) in [("a", 30, 2), ("b", 10, 1), ("c", 50, 2), ("d", 20, 3)] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            level,
+        });
+    }
+
+    assert_eq!(map.rank_by_score(&5), 0);
+    assert_eq!(map.rank_by_score(&10), 0);
+    assert_eq!(map.rank_by_score(&30), 2);
+    assert_eq!(map.rank_by_score(&31), 3);
+    assert_eq!(map.rank_by_score(&100), 4);
+
+    let by_score = (0..4)
+        .map(|n| map.nth_by_score(n).unwrap().name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(by_score, vec!["b", "d", "a", "c"]);
+    assert_eq!(map.nth_by_score(4), None);
+
+    // Elements with equal keys are ordered by their position in the backing storage.
+    assert_eq!(map.rank_by_level(&2), 1);
+    assert_eq!(map.rank_by_level(&3), 3);
+    let by_level = (0..4)
+        .map(|n| map.nth_by_level(n).unwrap().name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(by_level, vec!["b", "a", "c", "d"]);
+}
+
+#[test]
+fn test_rank_after_modify_and_remove() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, level) in [("a", 30, 2), ("b", 10, 1), ("c", 50, 2)] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            level,
+        });
+    }
+
+    map.modify_by_name(&"b".to_string(), |p| p.score = 60);
+    assert_eq!(map.rank_by_score(&60), 2);
+    assert_eq!(map.nth_by_score(0).unwrap().name, "a");
+    assert_eq!(map.nth_by_score(2).unwrap().name, "b");
+
+    map.remove_by_score(&30);
+    assert_eq!(map.rank_by_score(&60), 1);
+    assert_eq!(map.nth_by_score(0).unwrap().name, "c");
+    assert_eq!(map.nth_by_level(0).unwrap().name, "b");
+    assert_eq!(map.nth_by_score(2), None);
+
+    map.clear();
+    assert_eq!(map.rank_by_score(&60), 0);
+    assert_eq!(map.nth_by_score(0), None);
+}
+
+#[test]
+fn test_rank_matches_sorted_scan() {
+    let mut map = MultiIndexPlayerMap::default();
+    let mut state = 12345u64;
+    for i in 0..500u32 {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let level = (state >> 59) as u8;
+        map.insert(Player {
+            name: i.to_string(),
+            score: i * 7 % 1000,
+            level,
+        });
+        if i % 3 == 0 {
+            map.remove_by_name(&(i / 2).to_string());
+        }
+    }
+
+    let mut levels = map.iter().map(|(_, p)| p.level).collect::<Vec<_>>();
+    levels.sort();
+    for level in 0..=32u8 {
+        let expected = levels.iter().filter(|l| **l < level).count();
+        assert_eq!(map.rank_by_level(&level), expected);
+    }
+    for (n, level) in levels.iter().enumerate() {
+        assert_eq!(map.nth_by_level(n).unwrap().level, *level);
+    }
+    assert_eq!(map.nth_by_level(levels.len()), None);
+}
@@ -0,0 +1,82 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Status {
+    Open,
+    Cancelled,
+}
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    status: Status,
+}
+
+#[test]
+fn test_retain() {
+    let mut map = MultiIndexTestElementMap::default();
+    for order_id in 0..10 {
+        map.insert(TestElement {
+            order_id,
+            timestamp: order_id as u64 * 100,
+            status: if order_id % 3 == 0 {
+                Status::Cancelled
+            } else {
+                Status::Open
+            },
+        });
+    }
+
+    map.retain(|e| e.status == Status::Open);
+    assert_eq!(map.len(), 6);
+    assert!(map.get_by_status(&Status::Cancelled).is_empty());
+    assert!(map.get_by_order_id(&3).is_none());
+    assert!(map.get_by_timestamp(&600).is_none());
+    assert_eq!(map.iter_by_timestamp().count(), 6);
+
+    map.retain(|_| true);
+    assert_eq!(map.len(), 6);
+    map.retain(|_| false);
+    assert!(map.is_empty());
+    assert_eq!(map.iter_by_order_id().count(), 0);
+}
+
+#[test]
+fn test_extract_if() {
+    let mut map = MultiIndexTestElementMap::default();
+    for order_id in 0..10 {
+        map.insert(TestElement {
+            order_id,
+            timestamp: order_id as u64 * 100,
+            status: if order_id % 3 == 0 {
+                Status::Cancelled
+            } else {
+                Status::Open
+            },
+        });
+    }
+
+    // Elements are returned in order of their position in the backing storage.
+    let cancelled = map.extract_if(|e| e.status == Status::Cancelled);
+    assert_eq!(
+        cancelled.iter().map(|e| e.order_id).collect::<Vec<_>>(),
+        vec![0, 3, 6, 9]
+    );
+    assert_eq!(map.len(), 6);
+    assert_eq!(map.get_by_status(&Status::Open).len(), 6);
+    assert!(map.get_by_timestamp(&900).is_none());
+
+    assert!(map.extract_if(|e| e.status == Status::Cancelled).is_empty());
+
+    // Removed positions are reused, and the removed keys are free again.
+    for elem in cancelled {
+        map.insert(elem);
+    }
+    assert_eq!(map.len(), 10);
+    assert_eq!(map.get_by_order_id(&9).unwrap().timestamp, 900);
+}
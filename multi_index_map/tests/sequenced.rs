@@ -0,0 +1,154 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+#[multi_index_sequenced]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+}
+
+#[test]
+fn test_insertion_order_survives_slot_reuse() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+        });
+    }
+
+    // Removing frees slots in the backing storage, which are reused by later inserts,
+    //   but the sequenced index still records the order elements were inserted in.
+    map.remove_by_field1(&1);
+    map.remove_by_field2(&0);
+    map.insert(TestElement {
+        field1: 5,
+        field2: 1,
+    });
+    map.insert(TestElement {
+        field1: 6,
+        field2: 1,
+    });
+
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![3, 5, 6]
+    );
+    assert_eq!(map.iter_sequenced().len(), 3);
+    assert_eq!(
+        map.iter_sequenced()
+            .rev()
+            .map(|e| e.field1)
+            .collect::<Vec<_>>(),
+        vec![6, 5, 3]
+    );
+}
+
+#[test]
+fn test_push_front_and_back() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.push_back(TestElement {
+        field1: 1,
+        field2: 0,
+    });
+    map.push_front(TestElement {
+        field1: 2,
+        field2: 0,
+    });
+    map.push_back(TestElement {
+        field1: 3,
+        field2: 0,
+    });
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 1, 3]
+    );
+
+    let rejected = map
+        .try_push_front(TestElement {
+            field1: 3,
+            field2: 0,
+        })
+        .unwrap_err();
+    assert_eq!(rejected.element.field1, 3);
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 1, 3]
+    );
+
+    assert_eq!(map.front().unwrap().field1, 2);
+    assert_eq!(map.back().unwrap().field1, 3);
+}
+
+#[test]
+fn test_pop_front_and_back() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..4 {
+        map.insert(TestElement {
+            field1: i,
+            field2: 7,
+        });
+    }
+
+    assert_eq!(map.pop_front().unwrap().field1, 0);
+    assert_eq!(map.pop_back().unwrap().field1, 3);
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_field1(&0).is_none());
+    assert_eq!(map.get_by_field2(&7).len(), 2);
+
+    assert_eq!(map.pop_back().unwrap().field1, 2);
+    assert_eq!(map.pop_back().unwrap().field1, 1);
+    assert_eq!(map.pop_back(), None);
+    assert_eq!(map.pop_front(), None);
+    assert!(map.is_empty());
+    assert!(map.get_by_field2(&7).is_empty());
+}
+
+#[test]
+fn test_move_to_front_and_back() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..4 {
+        map.insert(TestElement {
+            field1: i,
+            field2: 0,
+        });
+    }
+
+    map.move_to_front_by_field1(&2).unwrap();
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 0, 1, 3]
+    );
+    map.move_to_back_by_field1(&0).unwrap();
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 1, 3, 0]
+    );
+    map.move_to_back_by_field1(&0).unwrap();
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 1, 3, 0]
+    );
+    assert!(map.move_to_front_by_field1(&42).is_none());
+
+    // Modifying keys leaves the position in the sequence unchanged.
+    map.modify_by_field1(&3, |e| e.field1 = 30);
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![2, 1, 30, 0]
+    );
+
+    map.clear();
+    assert_eq!(map.iter_sequenced().count(), 0);
+    map.insert(TestElement {
+        field1: 9,
+        field2: 0,
+    });
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>(),
+        vec![9]
+    );
+}
@@ -0,0 +1,171 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(hashed_unique, skip_none)]
+    external_id: Option<String>,
+    #[multi_index(ordered_unique, skip_none)]
+    rank: Option<u32>,
+    #[multi_index(hashed_non_unique, skip_none)]
+    group: Option<u32>,
+}
+
+#[test]
+fn test_none_is_not_a_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        external_id: None,
+        rank: None,
+        group: None,
+    });
+    map.insert(TestElement {
+        id: 2,
+        external_id: None,
+        rank: None,
+        group: None,
+    });
+    map.insert(TestElement {
+        id: 3,
+        external_id: Some("A".to_string()),
+        rank: Some(10),
+        group: Some(7),
+    });
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get_by_external_id("A").unwrap().id, 3);
+    assert_eq!(map.get_by_rank(&10).unwrap().id, 3);
+    assert_eq!(map.get_by_group(&7).len(), 1);
+    assert_eq!(map.iter_by_external_id().count(), 1);
+    assert_eq!(map.iter_by_rank().count(), 1);
+    assert_eq!(map.range_by_rank(..).count(), 1);
+
+    // Some values are still unique.
+    let rejected = map
+        .try_insert(TestElement {
+            id: 4,
+            external_id: Some("A".to_string()),
+            rank: None,
+            group: None,
+        })
+        .unwrap_err();
+    assert_eq!(rejected.element.id, 4);
+    assert!(map
+        .try_insert(TestElement {
+            id: 4,
+            external_id: None,
+            rank: Some(10),
+            group: None,
+        })
+        .is_err());
+    assert_eq!(map.len(), 3);
+    assert!(map.get_by_id(&4).is_none());
+}
+
+#[test]
+fn test_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        external_id: None,
+        rank: Some(1),
+        group: Some(7),
+    });
+    map.insert(TestElement {
+        id: 2,
+        external_id: Some("B".to_string()),
+        rank: None,
+        group: Some(7),
+    });
+    map.insert(TestElement {
+        id: 3,
+        external_id: Some("C".to_string()),
+        rank: Some(3),
+        group: None,
+    });
+
+    assert_eq!(map.remove_by_id(&1).unwrap().id, 1);
+    assert!(map.get_by_rank(&1).is_none());
+    assert_eq!(map.get_by_group(&7).len(), 1);
+
+    assert_eq!(map.remove_by_external_id(&"B".to_string()).unwrap().id, 2);
+    assert!(map.get_by_group(&7).is_empty());
+
+    assert_eq!(map.remove_by_rank(&3).unwrap().id, 3);
+    assert!(map.is_empty());
+    assert_eq!(map.iter_by_external_id().count(), 0);
+}
+
+#[test]
+fn test_modify_between_none_and_some() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        external_id: None,
+        rank: None,
+        group: None,
+    });
+    map.insert(TestElement {
+        id: 2,
+        external_id: Some("B".to_string()),
+        rank: Some(2),
+        group: Some(7),
+    });
+
+    map.modify_by_id(&1, |e| {
+        e.external_id = Some("A".to_string());
+        e.rank = Some(1);
+        e.group = Some(7);
+    });
+    assert_eq!(map.get_by_external_id("A").unwrap().id, 1);
+    assert_eq!(map.get_by_rank(&1).unwrap().id, 1);
+    assert_eq!(map.get_by_group(&7).len(), 2);
+
+    map.modify_by_external_id(&"B".to_string(), |e| {
+        e.external_id = None;
+        e.rank = None;
+        e.group = None;
+    });
+    assert!(map.get_by_external_id("B").is_none());
+    assert!(map.get_by_rank(&2).is_none());
+    assert_eq!(map.get_by_group(&7).len(), 1);
+    assert_eq!(
+        map.iter_by_rank().map(|e| e.id).collect::<Vec<_>>(),
+        vec![1]
+    );
+
+    map.modify_by_group(&7, |e| e.group = Some(8));
+    assert!(map.get_by_group(&7).is_empty());
+    assert_eq!(map.get_by_group(&8)[0].id, 1);
+
+    // Elements without a key can now be inserted freely.
+    map.insert(TestElement {
+        id: 3,
+        external_id: None,
+        rank: None,
+        group: None,
+    });
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "uniqueness constraint violated on field 'external_id'")]
+fn test_modify_uniqueness_violation() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        id: 1,
+        external_id: None,
+        rank: None,
+        group: None,
+    });
+    map.insert(TestElement {
+        id: 2,
+        external_id: Some("B".to_string()),
+        rank: None,
+        group: None,
+    });
+    map.modify_by_id(&1, |e| e.external_id = Some("B".to_string()));
+}
@@ -0,0 +1,291 @@
+use multi_index_map::{MultiIndexMap, UniquenessError};
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+#[multi_index_transactional]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    note: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum TestError {
+    Conflict,
+    Aborted,
+}
+
+impl<T> From<UniquenessError<T>> for TestError {
+    fn from(_: UniquenessError<T>) -> Self {
+        TestError::Conflict
+    }
+}
+
+#[test]
+fn test_transaction_commits() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name) in
+        [(1, 100, "alice"), (2, 200, "alice"), (3, 300, "bob")]
+    {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            note: String::new(),
+        });
+    }
+
+    let result = map.transaction(|tx| {
+        let id = tx.try_insert(TestElement {
+            order_id: 4,
+            timestamp: 400,
+            trader_name: "carol".to_string(),
+            note: String::new(),
+        })?;
+        tx.try_modify_by_order_id(&1, |e| e.timestamp = 150)?;
+        tx.remove_by_trader_name(&"bob".to_string());
+        assert_eq!(tx.get_by_element_id(id).unwrap().order_id, 4);
+        Ok::<_, TestError>(id)
+    });
+    let id = result.unwrap();
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get_by_element_id(id).unwrap().trader_name, "carol");
+    assert_eq!(map.get_by_timestamp(&150).unwrap().order_id, 1);
+    assert!(map.get_by_timestamp(&100).is_none());
+    assert!(map.get_by_order_id(&3).is_none());
+}
+
+#[test]
+fn test_transaction_rolls_back_on_error() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name) in
+        [(1, 100, "alice"), (2, 200, "alice"), (3, 300, "bob")]
+    {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            note: String::new(),
+        });
+    }
+    let before = map
+        .iter()
+        .map(|(idx, e)| (idx, e.clone()))
+        .collect::<Vec<_>>();
+
+    let result = map.transaction(|tx| {
+        tx.remove_by_order_id(&1);
+        tx.insert(TestElement {
+            order_id: 5,
+            timestamp: 500,
+            trader_name: "dave".to_string(),
+            note: String::new(),
+        });
+        tx.try_modify_by_trader_name(&"alice".to_string(), |e| e.note = "seen".to_string())?;
+        tx.try_modify_by_order_id(&3, |e| {
+            e.timestamp = 100;
+            e.trader_name = "alice".to_string();
+        })?;
+        tx.remove_by_timestamp(&200);
+        // Conflicts with the element inserted earlier in the transaction.
+        tx.try_insert(TestElement {
+            order_id: 5,
+            timestamp: 600,
+            trader_name: "erin".to_string(),
+            note: String::new(),
+        })?;
+        Ok(())
+    });
+    assert_eq!(result, Err(TestError::Conflict));
+
+    // The elements are restored to their original positions, along with every lookup table.
+    assert_eq!(
+        map.iter()
+            .map(|(idx, e)| (idx, e.clone()))
+            .collect::<Vec<_>>(),
+        before
+    );
+    assert_eq!(map.get_by_order_id(&1).unwrap().timestamp, 100);
+    assert_eq!(map.get_by_timestamp(&300).unwrap().trader_name, "bob");
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert!(map.get_by_order_id(&5).is_none());
+    assert!(map.get_by_timestamp(&500).is_none());
+}
+
+#[test]
+fn test_transaction_rolls_back_on_abort() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name) in
+        [(1, 100, "alice"), (2, 200, "alice"), (3, 300, "bob")]
+    {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            note: String::new(),
+        });
+    }
+    let before = map
+        .iter()
+        .map(|(idx, e)| (idx, e.clone()))
+        .collect::<Vec<_>>();
+
+    let result: Result<(), TestError> = map.transaction(|tx| {
+        tx.try_modify_by_order_id(&2, |e| e.order_id = 20)?;
+        tx.remove_by_order_id(&20);
+        tx.insert(TestElement {
+            order_id: 2,
+            timestamp: 250,
+            trader_name: "frank".to_string(),
+            note: String::new(),
+        });
+        Err(TestError::Aborted)
+    });
+    assert_eq!(result, Err(TestError::Aborted));
+    assert_eq!(
+        map.iter()
+            .map(|(idx, e)| (idx, e.clone()))
+            .collect::<Vec<_>>(),
+        before
+    );
+    assert_eq!(map.get_by_timestamp(&200).unwrap().order_id, 2);
+    assert!(map.get_by_timestamp(&250).is_none());
+
+    // The map is still usable after rolling back.
+    map.insert(TestElement {
+        order_id: 4,
+        timestamp: 400,
+        trader_name: "carol".to_string(),
+        note: String::new(),
+    });
+    assert_eq!(map.len(), 4);
+}
+
+#[test]
+fn test_transaction_rolls_back_on_panic() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name) in
+        [(1, 100, "alice"), (2, 200, "alice"), (3, 300, "bob")]
+    {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            note: String::new(),
+        });
+    }
+    let before = map
+        .iter()
+        .map(|(idx, e)| (idx, e.clone()))
+        .collect::<Vec<_>>();
+
+    // The closure changes every indexed key of the element before panicking.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.transaction(|tx| {
+            tx.remove_by_order_id(&3);
+            tx.try_modify_by_order_id(&1, |e| {
+                e.order_id = 10;
+                e.timestamp = 300;
+                e.trader_name = "carol".to_string();
+                panic!("Modification aborted");
+            })?;
+            Ok::<_, TestError>(())
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(
+        map.iter()
+            .map(|(idx, e)| (idx, e.clone()))
+            .collect::<Vec<_>>(),
+        before
+    );
+    assert_eq!(map.get_by_order_id(&1).unwrap().timestamp, 100);
+    assert_eq!(map.get_by_timestamp(&300).unwrap().order_id, 3);
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert!(map.get_by_order_id(&10).is_none());
+    assert!(map.get_by_trader_name("carol").is_empty());
+
+    // Panicking part way through modifying several elements also restores those already modified.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.transaction(|tx| {
+            tx.try_modify_by_trader_name(&"alice".to_string(), |e| {
+                if e.order_id == 2 {
+                    panic!("Modification aborted");
+                }
+                e.timestamp += 1000;
+                e.trader_name = "carol".to_string();
+            })?;
+            Ok::<_, TestError>(())
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(
+        map.iter()
+            .map(|(idx, e)| (idx, e.clone()))
+            .collect::<Vec<_>>(),
+        before
+    );
+    assert_eq!(map.get_by_timestamp(&100).unwrap().order_id, 1);
+    assert!(map.get_by_timestamp(&1100).is_none());
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert!(map.get_by_trader_name("carol").is_empty());
+}
+
+#[test]
+fn test_transaction_modify_and_update() {
+    let mut map = MultiIndexTestElementMap::default();
+    for (order_id, timestamp, trader_name) in
+        [(1, 100, "alice"), (2, 200, "alice"), (3, 300, "bob")]
+    {
+        map.insert(TestElement {
+            order_id,
+            timestamp,
+            trader_name: trader_name.to_string(),
+            note: String::new(),
+        });
+    }
+    let before = map
+        .iter()
+        .map(|(idx, e)| (idx, e.clone()))
+        .collect::<Vec<_>>();
+
+    map.transaction(|tx| {
+        tx.update_by_trader_name(&"alice".to_string(), |note| *note = "seen".to_string());
+        tx.update_by_order_id(&3, |note| *note = "checked".to_string());
+        tx.modify_by_order_id(&1, |e| e.timestamp = 150);
+        tx.modify_by_trader_name(&"bob".to_string(), |e| e.trader_name = "carol".to_string());
+        Ok::<_, TestError>(())
+    })
+    .unwrap();
+    assert_eq!(map.get_by_timestamp(&150).unwrap().note, "seen");
+    assert_eq!(map.get_by_order_id(&2).unwrap().note, "seen");
+    assert_eq!(map.get_by_trader_name("carol")[0].note, "checked");
+    assert!(map.get_by_trader_name("bob").is_empty());
+
+    // Violating a uniqueness constraint in modify_by_ panics, undoing the updates made beforehand.
+    let mut map = MultiIndexTestElementMap::default();
+    for (_, e) in before.iter() {
+        map.insert(e.clone());
+    }
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.transaction(|tx| {
+            tx.update_by_trader_name(&"alice".to_string(), |note| *note = "seen".to_string());
+            tx.modify_by_order_id(&1, |e| e.timestamp = 200);
+            Ok::<_, TestError>(())
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(
+        map.iter()
+            .map(|(idx, e)| (idx, e.clone()))
+            .collect::<Vec<_>>(),
+        before
+    );
+    assert_eq!(map.get_by_timestamp(&100).unwrap().order_id, 1);
+    assert_eq!(map.get_by_timestamp(&200).unwrap().order_id, 2);
+}
@@ -0,0 +1,144 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique, skip_none)]
+    client_ref: Option<u64>,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+}
+
+#[test]
+fn test_try_extend_inserts_batch() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        client_ref: Some(10),
+        trader_name: "alice".to_string(),
+    });
+
+    let ids = map
+        .try_extend(vec![
+            TestElement {
+                order_id: 2,
+                client_ref: Some(20),
+                trader_name: "alice".to_string(),
+            },
+            TestElement {
+                order_id: 3,
+                client_ref: None,
+                trader_name: "bob".to_string(),
+            },
+            TestElement {
+                order_id: 4,
+                client_ref: None,
+                trader_name: "bob".to_string(),
+            },
+        ])
+        .unwrap();
+    assert_eq!(ids.len(), 3);
+    assert_eq!(map.len(), 4);
+    assert_eq!(
+        map.get_by_element_id(ids[1]),
+        Some(&TestElement {
+            order_id: 3,
+            client_ref: None,
+            trader_name: "bob".to_string(),
+        })
+    );
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert_eq!(map.get_by_client_ref(&20).unwrap().order_id, 2);
+
+    assert!(map.try_extend(Vec::new()).unwrap().is_empty());
+}
+
+#[test]
+fn test_try_extend_reports_every_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        client_ref: Some(10),
+        trader_name: "alice".to_string(),
+    });
+
+    let batch = vec![
+        TestElement {
+            order_id: 2,
+            client_ref: Some(20),
+            trader_name: "bob".to_string(),
+        },
+        // Conflicts with the existing element on order_id.
+        TestElement {
+            order_id: 1,
+            client_ref: None,
+            trader_name: "bob".to_string(),
+        },
+        TestElement {
+            order_id: 3,
+            client_ref: Some(30),
+            trader_name: "bob".to_string(),
+        },
+        // Conflicts with the first element of the batch on client_ref.
+        TestElement {
+            order_id: 4,
+            client_ref: Some(20),
+            trader_name: "bob".to_string(),
+        },
+        // Conflicts with the existing element on client_ref.
+        TestElement {
+            order_id: 5,
+            client_ref: Some(10),
+            trader_name: "bob".to_string(),
+        },
+    ];
+    let err = map.try_extend(batch.clone()).unwrap_err();
+    // The first element is reported too, as it shares its client_ref with a later element of the batch.
+    assert_eq!(err.conflicts, vec![0, 1, 3, 4]);
+    assert_eq!(err.elements, batch);
+
+    // Nothing from the batch was inserted.
+    assert_eq!(map.len(), 1);
+    assert!(map.get_by_order_id(&2).is_none());
+    assert!(map.get_by_trader_name("bob").is_empty());
+    assert_eq!(map.get_by_client_ref(&10).unwrap().order_id, 1);
+}
+
+#[test]
+fn test_try_extend_reports_both_sides_of_batch_conflicts() {
+    let mut map = MultiIndexTestElementMap::default();
+
+    // None of these conflict with the map, only with each other.
+    let batch = vec![
+        TestElement {
+            order_id: 1,
+            client_ref: Some(10),
+            trader_name: "alice".to_string(),
+        },
+        TestElement {
+            order_id: 2,
+            client_ref: None,
+            trader_name: "alice".to_string(),
+        },
+        TestElement {
+            order_id: 1,
+            client_ref: Some(20),
+            trader_name: "bob".to_string(),
+        },
+        TestElement {
+            order_id: 3,
+            client_ref: Some(30),
+            trader_name: "bob".to_string(),
+        },
+        TestElement {
+            order_id: 1,
+            client_ref: Some(30),
+            trader_name: "carol".to_string(),
+        },
+    ];
+    let err = map.try_extend(batch).unwrap_err();
+    assert_eq!(err.conflicts, vec![0, 2, 3, 4]);
+    assert!(map.is_empty());
+}
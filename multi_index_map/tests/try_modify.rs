@@ -0,0 +1,140 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(hashed_non_unique)]
+    trader_name: String,
+    note: String,
+}
+
+#[test]
+fn test_try_modify_success() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 3,
+        timestamp: 300,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+
+    let modified = map
+        .try_modify_by_order_id(&1, |e| e.timestamp = 150)
+        .unwrap()
+        .unwrap();
+    assert_eq!(modified.timestamp, 150);
+    assert!(map.get_by_timestamp(&100).is_none());
+    assert_eq!(map.get_by_timestamp(&150).unwrap().order_id, 1);
+
+    assert_eq!(
+        map.try_modify_by_order_id(&4, |e| e.timestamp = 400),
+        Ok(None)
+    );
+
+    let modified = map
+        .try_modify_by_trader_name(&"alice".to_string(), |e| e.timestamp += 1)
+        .unwrap();
+    assert_eq!(modified.len(), 2);
+    assert_eq!(map.get_by_timestamp(&151).unwrap().order_id, 1);
+    assert_eq!(map.get_by_timestamp(&301).unwrap().order_id, 3);
+}
+
+#[test]
+fn test_try_modify_conflict_restores() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 3,
+        timestamp: 300,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+
+    // Changing the timestamp to that of another element is rejected, and the indexed fields restored.
+    let result = map.try_modify_by_order_id(&1, |e| {
+        e.timestamp = 200;
+        e.trader_name = "carol".to_string();
+    });
+    assert!(result.is_err());
+    assert_eq!(map.get_by_order_id(&1).unwrap().timestamp, 100);
+    assert_eq!(map.get_by_timestamp(&100).unwrap().order_id, 1);
+    assert_eq!(map.get_by_timestamp(&200).unwrap().order_id, 2);
+    assert_eq!(map.get_by_trader_name("alice").len(), 2);
+    assert!(map.get_by_trader_name("carol").is_empty());
+
+    // The map is still usable afterwards.
+    map.modify_by_order_id(&1, |e| e.timestamp = 250);
+    assert_eq!(map.get_by_timestamp(&250).unwrap().order_id, 1);
+}
+
+#[test]
+fn test_try_modify_non_unique_is_all_or_nothing() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        note: String::new(),
+    });
+    map.insert(TestElement {
+        order_id: 3,
+        timestamp: 300,
+        trader_name: "alice".to_string(),
+        note: String::new(),
+    });
+
+    // Element 1 moves to a free timestamp, then element 3 clashes with element 2,
+    //   so element 1 is also restored.
+    let mut next_timestamp = [150, 200].into_iter();
+    let result = map.try_modify_by_trader_name(&"alice".to_string(), |e| {
+        e.timestamp = next_timestamp.next().unwrap();
+        e.note = "modified".to_string();
+    });
+    assert!(result.is_err());
+    assert_eq!(map.get_by_order_id(&1).unwrap().timestamp, 100);
+    assert_eq!(map.get_by_order_id(&3).unwrap().timestamp, 300);
+    assert!(map.get_by_timestamp(&150).is_none());
+    assert_eq!(map.iter_by_timestamp().count(), 3);
+
+    // An element may take a key given up by an earlier element in the same modification.
+    let mut next_timestamp = [310, 100].into_iter();
+    map.try_modify_by_trader_name(&"alice".to_string(), |e| {
+        e.timestamp = next_timestamp.next().unwrap()
+    })
+    .unwrap();
+    assert_eq!(map.get_by_timestamp(&100).unwrap().order_id, 3);
+    assert_eq!(map.get_by_timestamp(&310).unwrap().order_id, 1);
+}
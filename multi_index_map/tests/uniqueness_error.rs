@@ -0,0 +1,120 @@
+use multi_index_map::{MultiIndexMap, UniquenessConflict};
+
+#[derive(MultiIndexMap, Clone, PartialEq, Debug)]
+#[multi_index_derive(Clone, Debug)]
+#[multi_index_composite(trader_desk(hashed_unique, fields(trader_name, desk)))]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    trader_name: String,
+    desk: u32,
+}
+
+#[test]
+fn test_insert_error_names_every_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        desk: 1,
+    });
+    let b = map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        desk: 1,
+    });
+
+    let err = map
+        .try_insert(TestElement {
+            order_id: 1,
+            timestamp: 200,
+            trader_name: "alice".to_string(),
+            desk: 1,
+        })
+        .unwrap_err();
+    assert_eq!(err.element.order_id, 1);
+    assert_eq!(
+        err.conflicts(),
+        &[
+            UniquenessConflict::new("order_id", a),
+            UniquenessConflict::new("timestamp", b),
+            UniquenessConflict::new("trader_desk", a),
+        ]
+    );
+    assert_eq!(err.conflicts()[1].index(), "timestamp");
+    assert_eq!(err.conflicts()[1].element_id(), b);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Uniqueness constraint violated on index 'order_id' by the element with id {}, index 'timestamp' by the element with id {}, index 'trader_desk' by the element with id {}",
+            a.index(),
+            b.index(),
+            a.index()
+        )
+    );
+}
+
+#[test]
+fn test_modify_error_names_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        desk: 1,
+    });
+    let b = map.insert(TestElement {
+        order_id: 2,
+        timestamp: 200,
+        trader_name: "bob".to_string(),
+        desk: 1,
+    });
+
+    let err = map
+        .try_modify_by_order_id(&1, |e| {
+            e.timestamp = 200;
+            e.desk = 2;
+        })
+        .unwrap_err();
+    assert_eq!(err.conflicts(), &[UniquenessConflict::new("timestamp", b)]);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Uniqueness constraint violated on index 'timestamp' by the element with id {}",
+            b.index()
+        )
+    );
+
+    // Modifying an element to keep its own keys is not a conflict.
+    assert!(map.try_modify_by_order_id(&1, |e| e.desk = 1).is_ok());
+}
+
+#[test]
+fn test_entry_insert_error_names_conflict() {
+    let mut map = MultiIndexTestElementMap::default();
+    let a = map.insert(TestElement {
+        order_id: 1,
+        timestamp: 100,
+        trader_name: "alice".to_string(),
+        desk: 1,
+    });
+
+    match map.entry_by_order_id(2) {
+        MultiIndexTestElementMapOrderIdEntry::Vacant(entry) => {
+            let err = entry
+                .insert(TestElement {
+                    order_id: 2,
+                    timestamp: 100,
+                    trader_name: "bob".to_string(),
+                    desk: 1,
+                })
+                .unwrap_err();
+            assert_eq!(err.conflicts(), &[UniquenessConflict::new("timestamp", a)]);
+        }
+        MultiIndexTestElementMapOrderIdEntry::Occupied(_) => panic!("Expected a vacant entry"),
+    }
+}
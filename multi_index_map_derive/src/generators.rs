@@ -3,7 +3,7 @@ use ::syn::{Field, Visibility};
 use proc_macro2::Ident;
 use syn::{Generics, Type};
 
-use crate::index_attributes::{ExtraAttributes, Ordering, Uniqueness};
+use crate::index_attributes::{ExtraAttributes, IntervalIndex, KeySource, Ordering, Uniqueness};
 
 // Struct to store generated identifiers for each field.
 // These are set once during the initial pass over the indexed fields,
@@ -14,34 +14,131 @@ pub(crate) struct FieldIdents {
     pub(crate) index_name: Ident,
     pub(crate) cloned_name: Ident,
     pub(crate) iter_name: Ident,
+    pub(crate) entry_name: Ident,
+    pub(crate) occupied_entry_name: Ident,
+    pub(crate) vacant_entry_name: Ident,
 }
 
 struct FieldInfo<'a> {
     vis: &'a Visibility,
     ty: &'a Type,
+    key_ty: &'a Type,
     str: &'a str,
 }
 
 pub(crate) const EXPECT_NAMED_FIELDS: &str =
     "Internal logic broken, all fields should have named identifiers";
 
+// TokenStream representing an owned copy of the key of an index, read from the element bound to `elem`.
+// Suitable for inserting into the lookup table.
+fn owned_key(
+    elem: &proc_macro2::TokenStream,
+    idents: &FieldIdents,
+    key_source: &KeySource,
+) -> proc_macro2::TokenStream {
+    let field_name = &idents.name;
+    match key_source {
+        KeySource::Field => quote! { #elem.#field_name.clone() },
+        KeySource::OptionalField | KeySource::Each => quote! { __mim_key.clone() },
+        KeySource::Composite(fields) => {
+            quote! { ::multi_index_map::CompositeKey((#(#elem.#fields.clone(),)*)) }
+        }
+        KeySource::Extractor(extractor) => quote! { #extractor(&#elem) },
+    }
+}
+
+// TokenStream representing a reference to the key of an index, read from the element bound to `elem`.
+// Single fields are borrowed directly, and composite keys are borrowed as a tuple of references to their fields,
+//   but extracted keys must be built first.
+fn borrowed_key(
+    elem: &proc_macro2::TokenStream,
+    idents: &FieldIdents,
+    key_source: &KeySource,
+) -> proc_macro2::TokenStream {
+    let field_name = &idents.name;
+    match key_source {
+        KeySource::Field => quote! { &#elem.#field_name },
+        KeySource::OptionalField | KeySource::Each => quote! { __mim_key },
+        KeySource::Composite(fields) => quote! {
+            ::multi_index_map::CompositeKeyRef::as_key(&(#(&#elem.#fields,)*))
+        },
+        KeySource::Extractor(extractor) => quote! { &#extractor(&#elem) },
+    }
+}
+
+// TokenStream representing whether the key of an index read from the element bound to `elem`
+//   differs from `other`, which holds the key as its accessors take it, comparing composite keys field by field.
+fn key_differs(
+    elem: &proc_macro2::TokenStream,
+    other: &proc_macro2::TokenStream,
+    idents: &FieldIdents,
+    key_source: &KeySource,
+) -> proc_macro2::TokenStream {
+    match key_source {
+        KeySource::Composite(fields) => {
+            let positions = (0..fields.len()).map(syn::Index::from);
+            quote! { (#(&#elem.#fields,)*) != (#(&#other.#positions,)*) }
+        }
+        _ => {
+            let key = borrowed_key(elem, idents, key_source);
+            quote! { #key != &#other }
+        }
+    }
+}
+
+// Wrap statements using the key of an index read from the element bound to `elem`,
+//   so for skip_none indexes they are only run when the field is Some, with the value bound to `__mim_key`,
+//   and for each indexes they are run for every item in the field, with the item bound to `__mim_key`.
+fn guard_key(
+    elem: &proc_macro2::TokenStream,
+    idents: &FieldIdents,
+    key_source: &KeySource,
+    stmts: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let field_name = &idents.name;
+    match key_source {
+        KeySource::OptionalField => quote! {
+            if let Some(__mim_key) = &#elem.#field_name {
+                #stmts
+            }
+        },
+        KeySource::Each => quote! {
+            for __mim_key in &#elem.#field_name {
+                #stmts
+            }
+        },
+        _ => stmts,
+    }
+}
+
 // For each indexed field generate a TokenStream representing the lookup table for that field
 // Each lookup table maps it's index to a position in the backing storage,
 // or multiple positions in the backing storage in the non-unique indexes.
 pub(crate) fn generate_lookup_tables<'a>(
-    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
     extra_attrs: &'a ExtraAttributes,
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + 'a {
-    fields.iter().map(|(f, idents, ordering, uniqueness)| {
-        let ty = &f.ty;
-        let index_name = &idents.index_name;
+    fields
+        .iter()
+        .map(|(f, idents, ordering, uniqueness, key_source)| {
+            let ty = lookup_key_type(&f.ty, key_source);
+            let index_name = &idents.index_name;
 
-        let field_type = index_field_type(ty, ordering, uniqueness, extra_attrs);
+            let field_type = index_field_type(&ty, ordering, uniqueness, extra_attrs);
 
-        quote! {
-            #index_name: #field_type,
-        }
-    })
+            quote! {
+                #index_name: #field_type,
+            }
+        })
+}
+
+// The type of the keys of the lookup table of an index, given the type of the key its accessors take.
+// Composite keys are wrapped so their lookup tables can be queried without cloning any fields.
+fn lookup_key_type(ty: &Type, key_source: &KeySource) -> Type {
+    match key_source {
+        KeySource::Composite(_) => syn::parse_quote! { ::multi_index_map::CompositeKey<#ty> },
+        _ => ty.clone(),
+    }
 }
 
 fn index_field_type(
@@ -76,20 +173,22 @@ fn index_field_type(
 // If lookup table data structures support `with_capacity`, change `default()` and `new()` calls to
 //   `with_capacity(n)`
 pub(crate) fn generate_lookup_table_init(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, ordering, _uniqueness)| {
-        let index_name = &idents.index_name;
+    fields
+        .iter()
+        .map(|(_f, idents, ordering, _uniqueness, _key_source)| {
+            let index_name = &idents.index_name;
 
-        match ordering {
-            Ordering::Hashed => quote! {
-                #index_name: ::std::collections::HashMap::default(),
-            },
-            Ordering::Ordered => quote! {
-                #index_name: ::std::collections::BTreeMap::new(),
-            },
-        }
-    })
+            match ordering {
+                Ordering::Hashed => quote! {
+                    #index_name: ::std::collections::HashMap::default(),
+                },
+                Ordering::Ordered => quote! {
+                    #index_name: ::std::collections::BTreeMap::new(),
+                },
+            }
+        })
 }
 
 // For each indexed field generate a TokenStream representing reserving capacity in the lookup table.
@@ -97,18 +196,20 @@ pub(crate) fn generate_lookup_table_init(
 // Currently `BTreeMap::extend_reserve()` is nightly-only and uses the trait default implementation, which does nothing.
 // Once this is implemented and stabilized, we will use it here to reserve capacity.
 pub(crate) fn generate_lookup_table_reserve(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, ordering, _uniqueness)| {
-        let index_name = &idents.index_name;
+    fields
+        .iter()
+        .map(|(_f, idents, ordering, _uniqueness, _key_source)| {
+            let index_name = &idents.index_name;
 
-        match ordering {
-            Ordering::Hashed => quote! {
-                self.#index_name.reserve(additional);
-            },
-            Ordering::Ordered => quote! {},
-        }
-    })
+            match ordering {
+                Ordering::Hashed => quote! {
+                    self.#index_name.reserve(additional);
+                },
+                Ordering::Ordered => quote! {},
+            }
+        })
 }
 
 // For each indexed field generate a TokenStream representing shrinking the lookup table.
@@ -116,42 +217,62 @@ pub(crate) fn generate_lookup_table_reserve(
 // For consistency, HashMaps are shrunk to the capacity of the backing storage
 // `BTreeMap` does not support shrinking.
 pub(crate) fn generate_lookup_table_shrink(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, ordering, _uniqueness)| {
-        let index_name = &idents.index_name;
+    fields
+        .iter()
+        .map(|(_f, idents, ordering, _uniqueness, _key_source)| {
+            let index_name = &idents.index_name;
 
-        match ordering {
-            Ordering::Hashed => quote! {
-                self.#index_name.shrink_to_fit();
-            },
-            Ordering::Ordered => quote! {},
-        }
-    })
+            match ordering {
+                Ordering::Hashed => quote! {
+                    self.#index_name.shrink_to_fit();
+                },
+                Ordering::Ordered => quote! {},
+            }
+        })
 }
 
 // For each indexed field generate a TokenStream representing getting the Entry for that field's lookup table
 pub(crate) fn generate_entries_for_insert(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, ordering, uniqueness)| {
+    fields.iter().map(|(_f, idents, ordering, uniqueness, key_source)| {
         let field_name = &idents.name;
         let index_name = &idents.index_name;
         let entry_name = format_ident!("{field_name}_entry");
+        let key = owned_key(&quote! { elem }, idents, key_source);
+
+        // Elements without a key in a skip_none index can never violate its uniqueness, so have no entry.
+        if let (KeySource::OptionalField, Uniqueness::Unique) = (key_source, uniqueness) {
+            let entry_type = match ordering {
+                Ordering::Hashed => quote! { ::std::collections::hash_map::Entry },
+                Ordering::Ordered => quote! { ::std::collections::btree_map::Entry },
+            };
+            return quote! {
+                let #entry_name = match &elem.#field_name {
+                    Some(__mim_key) => match self.#index_name.entry(#key) {
+                        #entry_type::Occupied(_) => return Err(self.__mim_uniqueness_error(elem, idx)),
+                        #entry_type::Vacant(e) => Some(e),
+                    },
+                    None => None,
+                };
+            };
+        }
 
         match uniqueness {
             Uniqueness::Unique => match ordering {
                 Ordering::Hashed => {
                     quote! {
-                        let #entry_name = match self.#index_name.entry(elem.#field_name.clone()) {
-                            ::std::collections::hash_map::Entry::Occupied(_) => return Err(::multi_index_map::UniquenessError(elem)),
+                        let #entry_name = match self.#index_name.entry(#key) {
+                            ::std::collections::hash_map::Entry::Occupied(_) => return Err(self.__mim_uniqueness_error(elem, idx)),
                             ::std::collections::hash_map::Entry::Vacant(e) => e,
                         };
                     }
                 }
                 Ordering::Ordered => quote! {
-                    let #entry_name = match self.#index_name.entry(elem.#field_name.clone()) {
-                        ::std::collections::btree_map::Entry::Occupied(_) => return Err(::multi_index_map::UniquenessError(elem)),
+                    let #entry_name = match self.#index_name.entry(#key) {
+                        ::std::collections::btree_map::Entry::Occupied(_) => return Err(self.__mim_uniqueness_error(elem, idx)),
                         ::std::collections::btree_map::Entry::Vacant(e) => e,
                     };
                 },
@@ -167,24 +288,39 @@ pub(crate) fn generate_entries_for_insert(
 //   whereas non-unique fields require inserting to the container of positions,
 //   creating a new container if necessary.
 pub(crate) fn generate_inserts_for_entries(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, _ordering, uniqueness)| {
-        let field_name = &idents.name;
-        let index_name = &idents.index_name;
-        let entry_name = format_ident!("{field_name}_entry");
+    fields
+        .iter()
+        .map(|(_f, idents, _ordering, uniqueness, key_source)| {
+            let field_name = &idents.name;
+            let index_name = &idents.index_name;
+            let entry_name = format_ident!("{field_name}_entry");
+            let key = owned_key(&quote! { elem }, idents, key_source);
 
-        match uniqueness {
-            Uniqueness::Unique => quote! {
-                #entry_name.insert(idx);
-            },
-            Uniqueness::NonUnique => quote! {
-                self.#index_name.entry(elem.#field_name.clone())
-                    .or_insert(::std::collections::BTreeSet::new())
-                    .insert(idx);
-            },
-        }
-    })
+            match uniqueness {
+                Uniqueness::Unique => match key_source {
+                    KeySource::OptionalField => quote! {
+                        if let Some(e) = #entry_name {
+                            e.insert(idx);
+                        }
+                    },
+                    _ => quote! {
+                        #entry_name.insert(idx);
+                    },
+                },
+                Uniqueness::NonUnique => guard_key(
+                    &quote! { elem },
+                    idents,
+                    key_source,
+                    quote! {
+                        self.#index_name.entry(#key)
+                            .or_insert(::std::collections::BTreeSet::new())
+                            .insert(idx);
+                    },
+                ),
+            }
+        })
 }
 
 // For each indexed field generate a TokenStream
@@ -201,13 +337,14 @@ pub(crate) fn generate_inserts_for_entries(
 //     + If there are exactly one index in the container, then the index has to be idx,
 //       remove the key from the lookup table
 pub(crate) fn generate_removes(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> Vec<::proc_macro2::TokenStream> {
     fields
         .iter()
-        .map(|(_f, idents, _ordering, uniqueness)| {
+        .map(|(_f, idents, _ordering, uniqueness, key_source)| {
             let field_name = &idents.name;
-            let field_name_string = stringify!(field_name);
+            let field_name_string = field_name.to_string();
+            let key = borrowed_key(&quote! { elem_orig }, idents, key_source);
             let error_msg = format!(
                 concat!(
                     "Internal invariants broken, ",
@@ -217,24 +354,33 @@ pub(crate) fn generate_removes(
             );
             let index_name = &idents.index_name;
 
-            match uniqueness {
-                Uniqueness::Unique => quote! {
-                    let _removed_elem = self.#index_name.remove(&elem_orig.#field_name);
+            let remove = match (uniqueness, key_source) {
+                // An item may be repeated within the collection, so idx may already have been removed.
+                (Uniqueness::NonUnique, KeySource::Each) => quote! {
+                    if let Some(elems) = self.#index_name.get_mut(__mim_key) {
+                        elems.remove(&idx);
+                        if elems.is_empty() {
+                            self.#index_name.remove(__mim_key);
+                        }
+                    }
                 },
-                Uniqueness::NonUnique => quote! {
-                    let key_to_remove = &elem_orig.#field_name;
-                    if let Some(elems) = self.#index_name.get_mut(key_to_remove) {
+                (Uniqueness::Unique, _) => quote! {
+                    let _removed_elem = self.#index_name.remove(#key);
+                },
+                (Uniqueness::NonUnique, _) => quote! {
+                    if let Some(elems) = self.#index_name.get_mut(#key) {
                         if elems.len() > 1 {
                             if !elems.remove(&idx){
                                 panic!(#error_msg);
                             }
                         } else {
-                            self.#index_name.remove(key_to_remove);
+                            self.#index_name.remove(#key);
                         }
                     }
 
                 },
-            }
+            };
+            guard_key(&quote! { elem_orig }, idents, key_source, remove)
         })
         .collect()
 }
@@ -242,16 +388,25 @@ pub(crate) fn generate_removes(
 // For each indexed field generate a TokenStream representing the clone the original value,
 //   so that we can compare after the modify is applied and adjust lookup tables as necessary
 pub(crate) fn generate_pre_modifies(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> Vec<::proc_macro2::TokenStream> {
     fields
         .iter()
-        .map(|(_f, idents, _, _)| {
-            let field_name = &idents.name;
+        .map(|(_f, idents, _, _, key_source)| {
             let orig_ident = &idents.cloned_name;
+            let key = match key_source {
+                // Keep the whole field, as the element may gain or lose keys.
+                KeySource::OptionalField | KeySource::Each => {
+                    let field_name = &idents.name;
+                    quote! { elem.#field_name.clone() }
+                }
+                // Keep only the fields, to compare in place after the modify.
+                KeySource::Composite(fields) => quote! { (#(elem.#fields.clone(),)*) },
+                _ => owned_key(&quote! { elem }, idents, key_source),
+            };
 
             quote! {
-                let #orig_ident = elem.#field_name.clone();
+                let #orig_ident = #key;
             }
         })
         .collect::<Vec<_>>()
@@ -270,13 +425,15 @@ pub(crate) fn generate_pre_modifies(
 //     + if the container is empty after removal, remove the old key, and insert idx to the new key
 //       (create a new container if necessary)
 pub(crate) fn generate_post_modifies(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> Vec<::proc_macro2::TokenStream> {
-    fields.iter().map(|(_f, idents, _ordering, uniqueness)| {
+    fields.iter().map(|(_f, idents, ordering, uniqueness, key_source)| {
         let field_name = &idents.name;
-        let field_name_string = stringify!(field_name);
+        let field_name_string = field_name.to_string();
         let orig_ident = &idents.cloned_name;
         let index_name = &idents.index_name;
+        let owned = owned_key(&quote! { elem }, idents, key_source);
+        let key_changed = key_differs(&quote! { elem }, &quote! { #orig_ident }, idents, key_source);
         let error_msg = format!(
             concat!(
                 "Internal invariants broken, ",
@@ -285,21 +442,97 @@ pub(crate) fn generate_post_modifies(
             field_name_string
         );
 
+        // For skip_none indexes the original is the whole Option,
+        //   so remove the original key and insert the new key only where each is present.
+        if let KeySource::OptionalField = key_source {
+            let (remove, insert) = match uniqueness {
+                Uniqueness::Unique => (
+                    quote! {
+                        self.#index_name.remove(__mim_key).expect(#error_msg);
+                    },
+                    quote! {
+                        if let Some(other_idx) = self.#index_name.insert(#owned, idx) {
+                            panic!(
+                                "Unable to modify element, uniqueness constraint violated on field '{}' by the element with id {}",
+                                #field_name_string, other_idx
+                            );
+                        }
+                    },
+                ),
+                Uniqueness::NonUnique => (
+                    quote! {
+                        let idxs = self.#index_name.get_mut(__mim_key).expect(#error_msg);
+                        if idxs.len() > 1 {
+                            if !(idxs.remove(&idx)) {
+                                panic!(#error_msg);
+                            }
+                        } else {
+                            self.#index_name.remove(__mim_key);
+                        }
+                    },
+                    quote! {
+                        self.#index_name.entry(#owned)
+                            .or_insert(::std::collections::BTreeSet::new())
+                            .insert(idx);
+                    },
+                ),
+            };
+            return quote! {
+                if elem.#field_name != #orig_ident {
+                    if let Some(__mim_key) = &#orig_ident {
+                        #remove
+                    }
+                    if let Some(__mim_key) = &elem.#field_name {
+                        #insert
+                    }
+                }
+            };
+        }
+
+        // For each indexes the original is the whole collection,
+        //   so diff the original and new items, removing idx from the keys which were removed,
+        //   and inserting idx under the keys which were added.
+        if let KeySource::Each = key_source {
+            let set_type = match ordering {
+                Ordering::Hashed => quote! { ::std::collections::HashSet },
+                Ordering::Ordered => quote! { ::std::collections::BTreeSet },
+            };
+            return quote! {
+                if elem.#field_name != #orig_ident {
+                    let orig_keys = #orig_ident.iter().collect::<#set_type<_>>();
+                    let new_keys = elem.#field_name.iter().collect::<#set_type<_>>();
+                    for &__mim_key in orig_keys.difference(&new_keys) {
+                        let idxs = self.#index_name.get_mut(__mim_key).expect(#error_msg);
+                        if !(idxs.remove(&idx)) {
+                            panic!(#error_msg);
+                        }
+                        if idxs.is_empty() {
+                            self.#index_name.remove(__mim_key);
+                        }
+                    }
+                    for &__mim_key in new_keys.difference(&orig_keys) {
+                        self.#index_name.entry(#owned)
+                            .or_insert(::std::collections::BTreeSet::new())
+                            .insert(idx);
+                    }
+                }
+            };
+        }
+
         match uniqueness {
             Uniqueness::Unique => quote! {
-                if elem.#field_name != #orig_ident {
+                if #key_changed {
                     let idx = self.#index_name.remove(&#orig_ident).expect(#error_msg);
-                    let orig_elem_idx = self.#index_name.insert(elem.#field_name.clone(), idx);
-                    if orig_elem_idx.is_some() {
+                    if let Some(other_idx) = self.#index_name.insert(#owned, idx) {
                         panic!(
-                            "Unable to insert element, uniqueness constraint violated on field '{}'",
-                            #field_name_string
+                            "Unable to modify element, uniqueness constraint violated on field '{}' by the element with id {}",
+                            #field_name_string, other_idx
                         );
                     }
                 }
             },
             Uniqueness::NonUnique => quote! {
-                if elem.#field_name != #orig_ident {
+                if #key_changed {
                     let idxs = self.#index_name.get_mut(&#orig_ident).expect(#error_msg);
                     if idxs.len() > 1 {
                         if !(idxs.remove(&idx)) {
@@ -308,7 +541,7 @@ pub(crate) fn generate_post_modifies(
                     } else {
                         self.#index_name.remove(&#orig_ident);
                     }
-                    self.#index_name.entry(elem.#field_name.clone())
+                    self.#index_name.entry(#owned)
                         .or_insert(::std::collections::BTreeSet::new())
                         .insert(idx);
                 }
@@ -318,15 +551,17 @@ pub(crate) fn generate_post_modifies(
 }
 
 pub(crate) fn generate_clears(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, _ordering, _uniqueness)| {
-        let index_name = &idents.index_name;
+    fields
+        .iter()
+        .map(|(_f, idents, _ordering, _uniqueness, _key_source)| {
+            let index_name = &idents.index_name;
 
-        quote! {
-            self.#index_name.clear();
-        }
-    })
+            quote! {
+                self.#index_name.clear();
+            }
+        })
 }
 
 // TokenStream representing the get_by_ accessor for this field.
@@ -343,7 +578,7 @@ fn generate_field_getter(
     let getter_name = format_ident!("get_by_{}", &field_idents.name);
     let index_name = &field_idents.index_name;
     let field_vis = &field_info.vis;
-    let field_type = &field_info.ty;
+    let key_type = &field_info.key_ty;
     let (_, types, _) = generics.split_for_impl();
 
     let key_bounds = match ordering {
@@ -359,7 +594,7 @@ fn generate_field_getter(
         Uniqueness::Unique => quote! {
             #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Option<&#element_name #types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
                 #key_bounds,
             {
                 Some(&self._store[*self.#index_name.get(key)?])
@@ -368,7 +603,7 @@ fn generate_field_getter(
         Uniqueness::NonUnique => quote! {
             #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Vec<&#element_name #types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
                 #key_bounds,
             {
                 if let Some(idxs) = self.#index_name.get(key) {
@@ -385,6 +620,96 @@ fn generate_field_getter(
     }
 }
 
+// TokenStream representing the count_by_, contains_, and distinct_count_ accessors for this field.
+// These only read the lookup table, so never allocate or touch the backing storage.
+fn generate_field_counters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+) -> proc_macro2::TokenStream {
+    let counter_name = format_ident!("count_by_{}", &field_idents.name);
+    let contains_name = format_ident!("contains_{}", &field_idents.name);
+    let distinct_counter_name = format_ident!("distinct_count_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let key_type = &field_info.key_ty;
+
+    let key_bounds = match ordering {
+        Ordering::Hashed => quote! {
+            __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized
+        },
+        Ordering::Ordered => quote! {
+            __MultiIndexMapKeyType: Ord + ?Sized
+        },
+    };
+
+    let count = match uniqueness {
+        Uniqueness::Unique => quote! {
+            usize::from(self.#index_name.contains_key(key))
+        },
+        Uniqueness::NonUnique => quote! {
+            self.#index_name.get(key).map_or(0, |idxs| idxs.len())
+        },
+    };
+
+    quote! {
+        #field_vis fn #counter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> usize
+        where
+            #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+            #key_bounds,
+        {
+            #count
+        }
+
+        #field_vis fn #contains_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> bool
+        where
+            #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+            #key_bounds,
+        {
+            self.#index_name.contains_key(key)
+        }
+
+        #field_vis fn #distinct_counter_name(&self) -> usize {
+            self.#index_name.len()
+        }
+    }
+}
+
+// TokenStream representing the keys_by_ and groups_by_ accessors for this non-unique field.
+// These walk the lookup table directly, so yield each distinct key once,
+//   in key order for ordered indexes, with the elements of each group in order of their position.
+fn generate_field_group_getters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let keys_getter_name = format_ident!("keys_by_{}", &field_idents.name);
+    let groups_getter_name = format_ident!("groups_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let (_, types, _) = generics.split_for_impl();
+
+    quote! {
+        #field_vis fn #keys_getter_name(&self) -> impl Iterator<Item = &#field_type> + '_ {
+            self.#index_name.keys().map(::std::borrow::Borrow::<#field_type>::borrow)
+        }
+
+        #field_vis fn #groups_getter_name(
+            &self,
+        ) -> impl Iterator<Item = (&#field_type, impl Iterator<Item = &#element_name #types> + '_)> + '_ {
+            self.#index_name
+                .iter()
+                .map(move |(key, idxs)| {
+                    let key = ::std::borrow::Borrow::<#field_type>::borrow(key);
+                    (key, idxs.iter().map(move |idx| &self._store[*idx]))
+                })
+        }
+    }
+}
+
 // TokenStream representing the get_mut_by_ accessor for this field.
 fn generate_field_mut_getter(
     field_idents: &FieldIdents,
@@ -498,7 +823,7 @@ fn generate_field_updater(
     let updater_name = format_ident!("update_by_{}", &field_idents.name);
     let index_name = &field_idents.index_name;
     let field_vis = &field_info.vis;
-    let field_type = &field_info.ty;
+    let key_type = &field_info.key_ty;
     let field_name_str = &field_info.str;
     let (_, element_types, _) = generics.split_for_impl();
 
@@ -519,7 +844,7 @@ fn generate_field_updater(
                 f: impl FnOnce(#(&mut #unindexed_types,)*)
             ) -> Option<&#element_name #element_types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
                 #key_bounds,
             {
                 let idx = *self.#index_name.get(key)?;
@@ -535,7 +860,7 @@ fn generate_field_updater(
                 mut f: impl FnMut(#(&mut #unindexed_types,)*)
             ) -> Vec<&#element_name #element_types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
                 #key_bounds,
             {
                 let empty = ::std::collections::BTreeSet::<usize>::new();
@@ -642,6 +967,65 @@ fn generate_field_modifier(
         },
     }
 }
+// TokenStream representing the try_modify_by_ accessor for this field.
+// Unlike modify_by_, a modification violating the uniqueness of any index is undone rather than panicking,
+//   see generate_try_modify_helpers.
+// For non-unique indexes this is all-or-nothing, so the elements already modified are restored in reverse order,
+//   which frees any key an earlier element gave up before that element takes it back.
+fn generate_field_try_modifier(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let try_modifier_name = format_ident!("try_modify_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let (_, types, _) = generics.split_for_impl();
+
+    match uniqueness {
+        Uniqueness::Unique => quote! {
+            #field_vis fn #try_modifier_name(
+                &mut self,
+                key: &#field_type,
+                f: impl FnOnce(&mut #element_name #types)
+            ) -> Result<Option<&#element_name #types>, ::multi_index_map::UniquenessError<()>> {
+                let Some(idx) = self.#index_name.get(key).copied() else {
+                    return Ok(None);
+                };
+                self.__mim_try_modify_at(idx, f)?;
+                Ok(Some(&self._store[idx]))
+            }
+        },
+        Uniqueness::NonUnique => quote! {
+            #field_vis fn #try_modifier_name(
+                &mut self,
+                key: &#field_type,
+                mut f: impl FnMut(&mut #element_name #types)
+            ) -> Result<Vec<&#element_name #types>, ::multi_index_map::UniquenessError<()>> {
+                let idxs = match self.#index_name.get(key) {
+                    Some(container) => container.iter().copied().collect::<Vec<usize>>(),
+                    None => Vec::new(),
+                };
+                let mut originals = Vec::with_capacity(idxs.len());
+                for idx in idxs.iter().copied() {
+                    match self.__mim_try_modify_at(idx, &mut f) {
+                        Ok(original) => originals.push((idx, original)),
+                        Err(e) => {
+                            for (idx, original) in originals.into_iter().rev() {
+                                self.__mim_restore_at(idx, original);
+                            }
+                            return Err(e);
+                        }
+                    }
+                }
+                Ok(idxs.into_iter().map(|idx| &self._store[idx]).collect())
+            }
+        },
+    }
+}
 
 fn generate_field_iter_getter(
     field_idents: &FieldIdents,
@@ -680,14 +1064,314 @@ fn generate_field_iter_getter(
     }
 }
 
-pub(crate) fn generate_iter_mut(
-    iter_mut_name: &proc_macro2::Ident,
-    element_name: &proc_macro2::Ident,
-    element_vis: &Visibility,
-    unindexed_types: &[&Type],
-    unindexed_idents: &[&Ident],
-    generics: &Generics,
-    iter_generics: &Generics,
+// TokenStream representing the range_by_ accessor for this field.
+// Only generated for ordered indexes, as these are backed by a BTreeMap which supports range queries.
+// For non-unique indexes we flatten each container of positions in the range,
+//   so elements with equal keys are yielded in the order they are stored in the container.
+fn generate_field_range_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let range_getter_name = format_ident!("range_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let key_type = &field_info.key_ty;
+    let (_, types, _) = generics.split_for_impl();
+
+    let range_action = match uniqueness {
+        Uniqueness::Unique => quote! {
+            self.#index_name
+                .range(range)
+                .map(move |(_, idx)| &self._store[*idx])
+        },
+        Uniqueness::NonUnique => quote! {
+            self.#index_name
+                .range(range)
+                .flat_map(|(_, idxs)| idxs.iter())
+                .map(move |idx| &self._store[*idx])
+        },
+    };
+
+    quote! {
+        #field_vis fn #range_getter_name<__MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+            &self,
+            range: __MultiIndexMapRangeType,
+        ) -> impl ::std::iter::DoubleEndedIterator<Item = &#element_name #types> + '_
+        where
+            #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+            __MultiIndexMapKeyType: Ord + ?Sized,
+            __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+        {
+            #range_action
+        }
+    }
+}
+
+// TokenStream representing the drain_by_ accessor for this field, and drain_range_by_ for ordered indexes.
+// The positions of the elements are collected in index order, then each is removed from the backing storage
+//   and every lookup table by __mim_remove_all_at, so the elements are returned through an owning iterator over a Vec.
+// Elements with no key in this index, ie. None in a skip_none index, are not drained.
+fn generate_field_drainers(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let drainer_name = format_ident!("drain_by_{}", &field_idents.name);
+    let range_drainer_name = format_ident!("drain_range_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let key_type = &field_info.key_ty;
+    let (_, types, _) = generics.split_for_impl();
+
+    let entry_idxs = match uniqueness {
+        Uniqueness::Unique => quote! { map(|(_, idx)| *idx) },
+        Uniqueness::NonUnique => quote! { flat_map(|(_, idxs)| idxs.iter().copied()) },
+    };
+
+    let range_drainer = match ordering {
+        Ordering::Hashed => quote! {},
+        Ordering::Ordered => quote! {
+            #field_vis fn #range_drainer_name<__MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+                &mut self,
+                range: __MultiIndexMapRangeType,
+            ) -> ::std::vec::IntoIter<#element_name #types>
+            where
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: Ord + ?Sized,
+                __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+            {
+                let idxs = self.#index_name.range(range).#entry_idxs.collect::<Vec<usize>>();
+                self.__mim_remove_all_at(idxs).into_iter()
+            }
+        },
+    };
+
+    quote! {
+        #field_vis fn #drainer_name(&mut self) -> ::std::vec::IntoIter<#element_name #types> {
+            let idxs = self.#index_name.iter().#entry_idxs.collect::<Vec<usize>>();
+            self.__mim_remove_all_at(idxs).into_iter()
+        }
+
+        #range_drainer
+    }
+}
+
+// TokenStream representing the accessors navigating to the elements with the key nearest a bound,
+//   first_by_, last_by_, ceil_by_, floor_by_, next_after_, and prev_before_ for this field.
+// Only generated for ordered indexes, each finds a single entry of the BTreeMap, from either end of a range.
+// For non-unique indexes all elements with the key of that entry are returned.
+fn generate_field_bound_getters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let key_type = &field_info.key_ty;
+    let (_, types, _) = generics.split_for_impl();
+
+    let (return_type, entry_to_elems) = match uniqueness {
+        Uniqueness::Unique => (
+            quote! { Option<&#element_name #types> },
+            quote! { .map(|(_, idx)| &self._store[*idx]) },
+        ),
+        Uniqueness::NonUnique => (
+            quote! { Vec<&#element_name #types> },
+            quote! {
+                .map(|(_, idxs)| idxs.iter().map(|idx| &self._store[*idx]).collect())
+                .unwrap_or_default()
+            },
+        ),
+    };
+
+    let end_getters = [
+        ("first_by", quote! { next }),
+        ("last_by", quote! { next_back }),
+    ]
+    .into_iter()
+    .map(|(name, end)| {
+        let getter_name = format_ident!("{}_{}", name, field_idents.name);
+        quote! {
+            #field_vis fn #getter_name(&self) -> #return_type {
+                self.#index_name.iter().#end()#entry_to_elems
+            }
+        }
+    });
+
+    // The range from each bound which the nearest key is found in, and the end of the range it is at.
+    let bound_getters = [
+        (
+            "ceil_by",
+            quote! { (::std::ops::Bound::Included(key), ::std::ops::Bound::Unbounded) },
+            quote! { next },
+        ),
+        (
+            "floor_by",
+            quote! { (::std::ops::Bound::Unbounded, ::std::ops::Bound::Included(key)) },
+            quote! { next_back },
+        ),
+        (
+            "next_after",
+            quote! { (::std::ops::Bound::Excluded(key), ::std::ops::Bound::Unbounded) },
+            quote! { next },
+        ),
+        (
+            "prev_before",
+            quote! { (::std::ops::Bound::Unbounded, ::std::ops::Bound::Excluded(key)) },
+            quote! { next_back },
+        ),
+    ]
+    .into_iter()
+    .map(|(name, range, end)| {
+        let getter_name = format_ident!("{}_{}", name, field_idents.name);
+        quote! {
+            #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> #return_type
+            where
+                #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: Ord + ?Sized,
+            {
+                self.#index_name
+                    .range::<__MultiIndexMapKeyType, _>(#range)
+                    .#end()
+                    #entry_to_elems
+            }
+        }
+    });
+
+    quote! {
+        #(#end_getters)*
+
+        #(#bound_getters)*
+    }
+}
+
+// TokenStream representing the pop_first_by_ and pop_last_by_ accessors for this field.
+// Only generated for ordered indexes, these remove the smallest or largest key from the lookup table,
+//   found by cloning the first or last key as BTreeMap::pop_first is newer than our MSRV,
+//   then remove the element(s) under it in the same way as the remove_by_ accessor.
+fn generate_field_poppers(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let (_, types, _) = generics.split_for_impl();
+
+    [
+        (
+            format_ident!("pop_first_by_{}", field_idents.name),
+            quote! { next },
+        ),
+        (
+            format_ident!("pop_last_by_{}", field_idents.name),
+            quote! { next_back },
+        ),
+    ]
+    .into_iter()
+    .map(|(popper_name, end)| match uniqueness {
+        Uniqueness::Unique => quote! {
+            #field_vis fn #popper_name(&mut self) -> Option<#element_name #types> {
+                let key = self.#index_name.keys().#end()?.clone();
+                let idx = self.#index_name.remove(&key)?;
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                Some(elem_orig)
+            }
+        },
+        Uniqueness::NonUnique => quote! {
+            #field_vis fn #popper_name(&mut self) -> Vec<#element_name #types> {
+                let key = self.#index_name.keys().#end().cloned();
+                if let Some(idxs) = key.and_then(|key| self.#index_name.remove(&key)) {
+                    let mut elems = Vec::with_capacity(idxs.len());
+                    for idx in idxs {
+                        let elem_orig = self._store.remove(idx);
+                        #(#removes)*
+                        elems.push(elem_orig)
+                    }
+                    elems
+                } else {
+                    Vec::new()
+                }
+            }
+        },
+    })
+    .collect()
+}
+
+// TokenStream representing the prefix_by_ accessor for this field.
+// Only generated for ordered indexes on String keys, where all keys starting with the prefix form a single range,
+//   bounded below by the prefix itself and above by the smallest string greater than any string starting with it.
+fn generate_field_prefix_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let prefix_getter_name = format_ident!("prefix_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let (_, types, _) = generics.split_for_impl();
+
+    let range_action = match uniqueness {
+        Uniqueness::Unique => quote! {
+            .map(move |(_, idx)| &self._store[*idx])
+        },
+        Uniqueness::NonUnique => quote! {
+            .flat_map(|(_, idxs)| idxs.iter())
+            .map(move |idx| &self._store[*idx])
+        },
+    };
+
+    quote! {
+        #field_vis fn #prefix_getter_name(
+            &self,
+            prefix: &str,
+        ) -> impl ::std::iter::DoubleEndedIterator<Item = &#element_name #types> + '_ {
+            let upper_bound = ::multi_index_map::prefix_upper_bound(prefix);
+            let upper_bound = match &upper_bound {
+                Some(upper_bound) => ::std::ops::Bound::Excluded(upper_bound.as_str()),
+                None => ::std::ops::Bound::Unbounded,
+            };
+            self.#index_name
+                .range::<str, _>((::std::ops::Bound::Included(prefix), upper_bound))
+                #range_action
+        }
+    }
+}
+
+// Whether the type is a String, types are only known syntactically here, so this matches any path ending in String.
+fn is_string_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "String"),
+        _ => false,
+    }
+}
+
+pub(crate) fn generate_iter_mut(
+    iter_mut_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+    generics: &Generics,
+    iter_generics: &Generics,
 ) -> proc_macro2::TokenStream {
     let (_, types, _) = generics.split_for_impl();
     let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
@@ -724,7 +1408,7 @@ pub(crate) fn generate_iter_mut(
 //   for the underlying storage via that field's lookup table.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_accessors<'a>(
-    indexed_fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
+    indexed_fields: &'a [(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
     unindexed_types: &'a [&Type],
     unindexed_idents: &'a [&Ident],
     element_name: &'a proc_macro2::Ident,
@@ -736,10 +1420,11 @@ pub(crate) fn generate_accessors<'a>(
 ) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
     indexed_fields
         .iter()
-        .map(move |(f, idents, ordering, uniqueness)| {
+        .map(move |(f, idents, ordering, uniqueness, key_source)| {
             let field_info = FieldInfo {
                 vis: &f.vis,
                 ty: &f.ty,
+                key_ty: &lookup_key_type(&f.ty, key_source),
                 str: &idents.name.to_string(),
             };
 
@@ -752,6 +1437,15 @@ pub(crate) fn generate_accessors<'a>(
                 generics,
             );
 
+            let counters = generate_field_counters(idents, &field_info, ordering, uniqueness);
+
+            let group_getters = match uniqueness {
+                Uniqueness::Unique => quote! {},
+                Uniqueness::NonUnique => {
+                    generate_field_group_getters(idents, &field_info, element_name, generics)
+                }
+            };
+
             let mut_getter = generate_field_mut_getter(
                 idents,
                 &field_info,
@@ -790,22 +1484,100 @@ pub(crate) fn generate_accessors<'a>(
                 generics,
             );
 
+            let drainers = generate_field_drainers(
+                idents,
+                &field_info,
+                element_name,
+                ordering,
+                uniqueness,
+                generics,
+            );
+
+            let try_modifier = generate_field_try_modifier(
+                idents,
+                &field_info,
+                element_name,
+                uniqueness,
+                generics,
+            );
+
             let iter_getter =
                 generate_field_iter_getter(idents, &field_info, ordering, iter_generics);
 
+            let range_getter = match ordering {
+                Ordering::Hashed => quote! {},
+                Ordering::Ordered => generate_field_range_getter(
+                    idents,
+                    &field_info,
+                    element_name,
+                    uniqueness,
+                    generics,
+                ),
+            };
+
+            let bound_getters = match ordering {
+                Ordering::Hashed => quote! {},
+                Ordering::Ordered => generate_field_bound_getters(
+                    idents,
+                    &field_info,
+                    element_name,
+                    uniqueness,
+                    generics,
+                ),
+            };
+
+            let poppers = match ordering {
+                Ordering::Hashed => quote! {},
+                Ordering::Ordered => generate_field_poppers(
+                    idents,
+                    &field_info,
+                    element_name,
+                    uniqueness,
+                    removes,
+                    generics,
+                ),
+            };
+
+            let prefix_getter = match ordering {
+                Ordering::Ordered if is_string_type(&f.ty) => generate_field_prefix_getter(
+                    idents,
+                    &field_info,
+                    element_name,
+                    uniqueness,
+                    generics,
+                ),
+                _ => quote! {},
+            };
+
             // Put all these TokenStreams together, and put a TokenStream representing the iter_by_ accessor
             //   on the end.
             quote! {
                 #getter
 
+                #counters
+
                 #mut_getter
 
                 #remover
 
+                #poppers
+
+                #drainers
+
                 #modifier
 
+                #try_modifier
+
                 #updater
 
+                #range_getter
+
+                #bound_getters
+
+                #prefix_getter
+
+                #group_getters
+
                 #iter_getter
             }
         })
@@ -815,7 +1587,7 @@ pub(crate) fn generate_accessors<'a>(
 //   via that field,
 // such that the elements are accessed in an order defined by the index rather than the backing storage.
 pub(crate) fn generate_iterators<'a>(
-    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
+    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
     element_name: &'a proc_macro2::Ident,
     generics: &'a Generics,
     iter_generics: &'a Generics,
@@ -823,7 +1595,7 @@ pub(crate) fn generate_iterators<'a>(
     let (_, element_types, _) = generics.split_for_impl();
     let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
 
-    fields.iter().map(move |(f, idents, ordering, uniqueness)| {
+    fields.iter().map(move |(f, idents, ordering, uniqueness, key_source)| {
         let field_name = &idents.name;
         let field_vis = &f.vis;
         let field_name_string = field_name.to_string();
@@ -831,7 +1603,7 @@ pub(crate) fn generate_iterators<'a>(
             "Internal invariants broken, found empty slice in non_unique index '{field_name_string}'"
         );
         let iter_name = &idents.iter_name;
-        let ty = &f.ty;
+        let ty = lookup_key_type(&f.ty, key_source);
 
         // TokenStream representing the actual type of the iterator
         let iter_type = match uniqueness {
@@ -941,93 +1713,1695 @@ pub(crate) fn generate_iterators<'a>(
     })
 }
 
-// Build the final output using quasi-quoting
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn generate_expanded(
-    extra_attrs: &ExtraAttributes,
-    generics: &Generics,
-    map_name: &proc_macro2::Ident,
-    element_name: &proc_macro2::Ident,
+// TokenStreams for an index which is maintained separately from the lookup tables of the indexed fields,
+//   eg. the sequenced index, or a bitmap index.
+// Each part is spliced into the generated map alongside the equivalent parts for the indexed fields:
+//   - `lookup_table_field` and `lookup_table_init` are added to the map struct and its constructors
+//   - `insert` runs in `try_insert` once the element has passed all uniqueness checks, with its position as `idx`
+//   - `remove` runs after an element is removed from the backing storage, like each of `generate_removes`
+//   - `pre_modify` and `post_modify` run around each modification, like each of `generate_pre_modifies`
+//     and `generate_post_modifies`, these are empty for indexes which do not depend on the fields of the element
+//   - `clear`, `reserve`, and `shrink` run alongside those of the lookup tables
+// The methods accessing these indexes are generated separately,
+//   as removing elements requires the `remove` of every index to be known.
+pub(crate) struct AuxiliaryIndex {
+    pub(crate) lookup_table_field: proc_macro2::TokenStream,
+    pub(crate) lookup_table_init: proc_macro2::TokenStream,
+    pub(crate) insert: proc_macro2::TokenStream,
+    pub(crate) remove: proc_macro2::TokenStream,
+    pub(crate) pre_modify: proc_macro2::TokenStream,
+    pub(crate) post_modify: proc_macro2::TokenStream,
+    pub(crate) clear: proc_macro2::TokenStream,
+    pub(crate) reserve: proc_macro2::TokenStream,
+    pub(crate) shrink: proc_macro2::TokenStream,
+}
+
+// Generate the sequenced index, which records the order elements were inserted in,
+//   as a doubly linked list of positions in the backing storage.
+pub(crate) fn generate_sequenced_index() -> AuxiliaryIndex {
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            _sequenced_index: ::multi_index_map::Sequence,
+        },
+        lookup_table_init: quote! {
+            _sequenced_index: ::multi_index_map::Sequence::default(),
+        },
+        insert: quote! {
+            self._sequenced_index.push_back(idx);
+        },
+        remove: quote! {
+            self._sequenced_index.remove(idx);
+        },
+        pre_modify: quote! {},
+        post_modify: quote! {},
+        clear: quote! {
+            self._sequenced_index.clear();
+        },
+        reserve: quote! {
+            self._sequenced_index.reserve(additional);
+        },
+        shrink: quote! {
+            self._sequenced_index.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the methods accessing the sequenced index.
+// Elements are pushed to the back of the sequence by `try_insert`, or to the front by `try_push_front`,
+//   and can be moved to either end via the unique indexes.
+// Popping from either end removes the element from all lookup tables, in the same way as `remove_by_`.
+pub(crate) fn generate_sequenced_methods(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
     element_vis: &Visibility,
-    entries_for_insert: impl Iterator<Item = proc_macro2::TokenStream>,
-    inserts_for_entries: impl Iterator<Item = proc_macro2::TokenStream>,
-    accessors: impl Iterator<Item = proc_macro2::TokenStream>,
-    iterators: impl Iterator<Item = proc_macro2::TokenStream>,
-    clears: impl Iterator<Item = proc_macro2::TokenStream>,
-    lookup_table_fields: impl Iterator<Item = proc_macro2::TokenStream>,
-    lookup_table_fields_init: impl Iterator<Item = proc_macro2::TokenStream>,
-    lookup_table_fields_default: impl Iterator<Item = proc_macro2::TokenStream>,
-    lookup_table_fields_shrink: impl Iterator<Item = proc_macro2::TokenStream>,
-    lookup_table_fields_reserve: impl Iterator<Item = proc_macro2::TokenStream>,
-    iter_mut_name: &proc_macro2::Ident,
-    iter_mut: proc_macro2::TokenStream,
-    iter_generics: &Generics,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
 ) -> proc_macro2::TokenStream {
-    let derives = &extra_attrs.derives;
-    let (impls, types, where_clause) = generics.split_for_impl();
-    let (_, iter_types, _) = iter_generics.split_for_impl();
+    let (_, types, _) = generics.split_for_impl();
 
-    quote! {
-        #(#[#derives])*
-        #element_vis struct #map_name #impls {
-            _store: ::multi_index_map::slab::Slab<#element_name #types>,
-            #(#lookup_table_fields)*
-        }
+    // Moving elements requires identifying a single element, so this is only possible through unique indexes.
+    let movers = indexed_fields
+        .iter()
+        .filter(|(_, _, _, uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(f, idents, ordering, _, key_source)| {
+            let field_vis = &f.vis;
+            let key_type = lookup_key_type(&f.ty, key_source);
+            let index_name = &idents.index_name;
+            let to_front_name = format_ident!("move_to_front_by_{}", idents.name);
+            let to_back_name = format_ident!("move_to_back_by_{}", idents.name);
+            let key_bounds = match ordering {
+                Ordering::Hashed => quote! {
+                    __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized
+                },
+                Ordering::Ordered => quote! {
+                    __MultiIndexMapKeyType: Ord + ?Sized
+                },
+            };
 
-        impl #impls Default for #map_name #types #where_clause {
-            fn default() -> Self {
-                Self {
-                    _store: ::multi_index_map::slab::Slab::default(),
-                    #(#lookup_table_fields_default)*
+            quote! {
+                #field_vis fn #to_front_name<__MultiIndexMapKeyType>(&mut self, key: &__MultiIndexMapKeyType) -> Option<&#element_name #types>
+                where
+                    #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                    #key_bounds,
+                {
+                    let idx = *self.#index_name.get(key)?;
+                    self._sequenced_index.move_to_front(idx);
+                    Some(&self._store[idx])
                 }
-            }
-        }
 
-        impl #impls #map_name #types #where_clause {
-            #element_vis fn with_capacity(n: usize) -> Self {
-                Self {
-                    _store: ::multi_index_map::slab::Slab::with_capacity(n),
-                    #(#lookup_table_fields_init)*
+                #field_vis fn #to_back_name<__MultiIndexMapKeyType>(&mut self, key: &__MultiIndexMapKeyType) -> Option<&#element_name #types>
+                where
+                    #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                    #key_bounds,
+                {
+                    let idx = *self.#index_name.get(key)?;
+                    self._sequenced_index.move_to_back(idx);
+                    Some(&self._store[idx])
                 }
             }
+        });
 
-            #element_vis fn capacity(&self) -> usize {
-                self._store.capacity()
+    quote! {
+            #element_vis fn try_push_back(&mut self, elem: #element_name #types) -> Result<&#element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+                let id = self.try_insert(elem)?;
+                Ok(&self._store[id.index()])
             }
 
-            #element_vis fn len(&self) -> usize {
-                self._store.len()
+            #element_vis fn push_back(&mut self, elem: #element_name #types) -> &#element_name #types {
+                let id = self.insert(elem);
+                &self._store[id.index()]
             }
 
-            #element_vis fn is_empty(&self) -> bool {
-                self._store.is_empty()
+            #element_vis fn try_push_front(&mut self, elem: #element_name #types) -> Result<&#element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+                let idx = self.try_insert(elem)?.index();
+                self._sequenced_index.move_to_front(idx);
+                Ok(&self._store[idx])
             }
 
-            // reserving is slow. users are in control of when to reserve
-            #element_vis fn reserve(&mut self, additional: usize) {
-                self._store.reserve(additional);
-                #(#lookup_table_fields_reserve)*
+            #element_vis fn push_front(&mut self, elem: #element_name #types) -> &#element_name #types {
+                self.try_push_front(elem).expect("Unable to insert element")
             }
 
-            // shrinking is slow. users are in control of when to shrink
+            #element_vis fn front(&self) -> Option<&#element_name #types> {
+                Some(&self._store[self._sequenced_index.front()?])
+            }
+
+            #element_vis fn back(&self) -> Option<&#element_name #types> {
+                Some(&self._store[self._sequenced_index.back()?])
+            }
+
+            #element_vis fn pop_front(&mut self) -> Option<#element_name #types> {
+                let idx = self._sequenced_index.front()?;
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                Some(elem_orig)
+            }
+
+            #element_vis fn pop_back(&mut self) -> Option<#element_name #types> {
+                let idx = self._sequenced_index.back()?;
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                Some(elem_orig)
+            }
+
+            // Iterate over the elements in the order of the sequenced index, rather than the backing storage.
+            #element_vis fn iter_sequenced(&self) -> impl ::std::iter::DoubleEndedIterator<Item = &#element_name #types> + ::std::iter::ExactSizeIterator + '_ {
+                self._sequenced_index.iter().map(move |idx| &self._store[idx])
+            }
+
+            #(#movers)*
+    }
+}
+
+// Generate the random access index, which records a logical position for each element,
+//   elements are appended by `try_insert`.
+pub(crate) fn generate_random_access_index() -> AuxiliaryIndex {
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            _random_access_index: ::multi_index_map::RandomAccess,
+        },
+        lookup_table_init: quote! {
+            _random_access_index: ::multi_index_map::RandomAccess::default(),
+        },
+        insert: quote! {
+            self._random_access_index.push(idx);
+        },
+        remove: quote! {
+            self._random_access_index.remove(idx);
+        },
+        pre_modify: quote! {},
+        post_modify: quote! {},
+        clear: quote! {
+            self._random_access_index.clear();
+        },
+        reserve: quote! {
+            self._random_access_index.reserve(additional);
+        },
+        shrink: quote! {
+            self._random_access_index.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the methods accessing the random access index.
+// Inserting at a position appends the element with `try_insert`, then moves it into place,
+//   so the position is checked first to avoid leaving an element in the map when panicking.
+pub(crate) fn generate_random_access_methods(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    // Positions identify a single element, so can only be looked up through unique indexes.
+    let position_getters = indexed_fields
+        .iter()
+        .filter(|(_, _, _, uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(f, idents, ordering, _, key_source)| {
+            let field_vis = &f.vis;
+            let key_type = lookup_key_type(&f.ty, key_source);
+            let index_name = &idents.index_name;
+            let getter_name = format_ident!("position_of_{}", idents.name);
+            let key_bounds = match ordering {
+                Ordering::Hashed => quote! {
+                    __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized
+                },
+                Ordering::Ordered => quote! {
+                    __MultiIndexMapKeyType: Ord + ?Sized
+                },
+            };
+
+            quote! {
+                #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Option<usize>
+                where
+                    #key_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                    #key_bounds,
+                {
+                    let idx = *self.#index_name.get(key)?;
+                    Some(self._random_access_index.position(idx))
+                }
+            }
+        });
+
+    quote! {
+            #element_vis fn nth(&self, position: usize) -> Option<&#element_name #types> {
+                Some(&self._store[self._random_access_index.get(position)?])
+            }
+
+            /// Panics if `position > len`.
+            #element_vis fn try_insert_at(&mut self, position: usize, elem: #element_name #types) -> Result<&#element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+                let len = self._random_access_index.len();
+                if position > len {
+                    panic!("insertion position (is {position}) should be <= len (is {len})");
+                }
+                let idx = self.try_insert(elem)?.index();
+                self._random_access_index.move_to(idx, position);
+                Ok(&self._store[idx])
+            }
+
+            /// Panics if `position > len`.
+            #element_vis fn insert_at(&mut self, position: usize, elem: #element_name #types) -> &#element_name #types {
+                self.try_insert_at(position, elem).expect("Unable to insert element")
+            }
+
+            #element_vis fn remove_at(&mut self, position: usize) -> Option<#element_name #types> {
+                let idx = self._random_access_index.get(position)?;
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                Some(elem_orig)
+            }
+
+            // Iterate over the elements in the order of their positions, rather than the backing storage.
+            #element_vis fn iter_random_access(&self) -> impl ::std::iter::DoubleEndedIterator<Item = &#element_name #types> + ::std::iter::ExactSizeIterator + '_ {
+                self._random_access_index.iter().map(move |idx| &self._store[idx])
+            }
+
+            #(#position_getters)*
+    }
+}
+
+// Generate the generations of the positions in the backing storage, which are bumped as elements are removed.
+pub(crate) fn generate_generations() -> AuxiliaryIndex {
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            _generations: ::multi_index_map::Generations,
+        },
+        lookup_table_init: quote! {
+            _generations: ::multi_index_map::Generations::default(),
+        },
+        insert: quote! {
+            self._generations.insert(idx);
+        },
+        remove: quote! {
+            self._generations.remove(idx);
+        },
+        pre_modify: quote! {},
+        post_modify: quote! {},
+        clear: quote! {
+            self._generations.clear();
+        },
+        reserve: quote! {
+            self._generations.reserve(additional);
+        },
+        shrink: quote! {
+            self._generations.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the methods accessing an element by an ElementHandle,
+//   which check the generation of the handle then delegate to the equivalent element id method.
+pub(crate) fn generate_handle_methods(
+    element_name: &Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    quote! {
+        // Handles are only created alongside the element they refer to, never from a bare ElementId,
+        //   as an ElementId may already refer to a later element reusing the same position.
+        #element_vis fn try_insert_with_handle(
+            &mut self,
+            elem: #element_name #types
+        ) -> Result<::multi_index_map::ElementHandle, ::multi_index_map::UniquenessError<#element_name #types>> {
+            let id = self.try_insert(elem)?;
+            Ok(::multi_index_map::ElementHandle::new(id, self._generations.get(id.index())))
+        }
+
+        #element_vis fn insert_with_handle(&mut self, elem: #element_name #types) -> ::multi_index_map::ElementHandle {
+            self.try_insert_with_handle(elem).expect("Unable to insert element")
+        }
+
+        #element_vis fn iter_with_handles(&self) -> impl Iterator<Item = (::multi_index_map::ElementHandle, &#element_name #types)> + '_ {
+            self._store.iter().map(move |(idx, elem)| {
+                let id = ::multi_index_map::ElementId::from_index(idx);
+                (::multi_index_map::ElementHandle::new(id, self._generations.get(idx)), elem)
+            })
+        }
+
+        #element_vis fn contains_handle(&self, handle: ::multi_index_map::ElementHandle) -> bool {
+            self._store.contains(handle.element_id().index())
+                && self._generations.get(handle.element_id().index()) == handle.generation()
+        }
+
+        #element_vis fn get_by_handle(&self, handle: ::multi_index_map::ElementHandle) -> Option<&#element_name #types> {
+            if !self.contains_handle(handle) {
+                return None;
+            }
+            self.get_by_element_id(handle.element_id())
+        }
+
+        #element_vis fn update_by_handle(
+            &mut self,
+            handle: ::multi_index_map::ElementHandle,
+            f: impl FnOnce(#(&mut #unindexed_types,)*)
+        ) -> Option<&#element_name #types> {
+            if !self.contains_handle(handle) {
+                return None;
+            }
+            self.update_by_element_id(handle.element_id(), f)
+        }
+
+        #element_vis fn modify_by_handle(
+            &mut self,
+            handle: ::multi_index_map::ElementHandle,
+            f: impl FnOnce(&mut #element_name #types)
+        ) -> Option<&#element_name #types> {
+            if !self.contains_handle(handle) {
+                return None;
+            }
+            self.modify_by_element_id(handle.element_id(), f)
+        }
+
+        #element_vis fn remove_by_handle(&mut self, handle: ::multi_index_map::ElementHandle) -> Option<#element_name #types> {
+            if !self.contains_handle(handle) {
+                return None;
+            }
+            self.remove_by_element_id(handle.element_id())
+        }
+    }
+}
+
+// Generate a bitmap index for the given field, mapping each value of the field to a bitmap of the positions
+//   in the backing storage of the elements with that value.
+// Bitmaps hold u32 positions, so the map can hold at most u32::MAX elements while it has a bitmap index.
+pub(crate) fn generate_bitmap_index(
+    field: &Field,
+    idents: &FieldIdents,
+    extra_attrs: &ExtraAttributes,
+) -> AuxiliaryIndex {
+    let field_name = &idents.name;
+    let field_type = &field.ty;
+    let index_name = &idents.index_name;
+    let orig_ident = &idents.cloned_name;
+    let hasher = &extra_attrs.hasher;
+    let error_msg = format!(
+        "Internal invariants broken, unable to find element in index '{field_name}' despite being present in another"
+    );
+
+    // try_insert has already rejected any element whose position does not fit in a u32.
+    let insert = quote! {
+        self.#index_name.entry(elem.#field_name.clone()).or_default().insert(idx as u32);
+    };
+
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            #index_name: ::std::collections::HashMap<#field_type, ::multi_index_map::roaring::RoaringBitmap, #hasher>,
+        },
+        lookup_table_init: quote! {
+            #index_name: ::std::collections::HashMap::default(),
+        },
+        insert: insert.clone(),
+        remove: quote! {
+            if let Some(bitmap) = self.#index_name.get_mut(&elem_orig.#field_name) {
+                bitmap.remove(idx as u32);
+                if bitmap.is_empty() {
+                    self.#index_name.remove(&elem_orig.#field_name);
+                }
+            }
+        },
+        pre_modify: quote! {
+            let #orig_ident = elem.#field_name.clone();
+        },
+        post_modify: quote! {
+            if elem.#field_name != #orig_ident {
+                let bitmap = self.#index_name.get_mut(&#orig_ident).expect(#error_msg);
+                bitmap.remove(idx as u32);
+                if bitmap.is_empty() {
+                    self.#index_name.remove(&#orig_ident);
+                }
+                #insert
+            }
+        },
+        clear: quote! {
+            self.#index_name.clear();
+        },
+        reserve: quote! {
+            self.#index_name.reserve(additional);
+        },
+        shrink: quote! {
+            self.#index_name.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the check at the start of try_insert that the position of the new element fits in the bitmap indexes,
+//   so an element which does not fit is rejected before any lookup table is changed.
+// No unique index is violated, so the element is returned with no conflicts.
+pub(crate) fn generate_bitmap_position_check() -> proc_macro2::TokenStream {
+    quote! {
+        if u32::try_from(idx).is_err() {
+            return Err(::multi_index_map::UniquenessError::new(elem, Vec::new()));
+        }
+    }
+}
+
+// Generate the methods accessing the bitmap indexes.
+// For each bitmap index, elements are looked up, counted, removed, or modified by value,
+//   and the bitmap for a value is exposed so bitmaps from several indexes can be combined,
+//   with the matching elements then retrieved through `iter_bitmap`.
+pub(crate) fn generate_bitmap_methods(
+    bitmap_fields: &[(Field, FieldIdents)],
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let accessors = bitmap_fields.iter().map(|(f, idents)| {
+        let field_vis = &f.vis;
+        let field_type = &f.ty;
+        let index_name = &idents.index_name;
+        let field_name_str = idents.name.to_string();
+        let getter_name = format_ident!("get_by_{}", idents.name);
+        let counter_name = format_ident!("count_by_{}", idents.name);
+        let contains_name = format_ident!("contains_{}", idents.name);
+        let distinct_counter_name = format_ident!("distinct_count_{}", idents.name);
+        let bitmap_getter_name = format_ident!("bitmap_by_{}", idents.name);
+        let iter_getter_name = format_ident!("iter_by_{}", idents.name);
+        let remover_name = format_ident!("remove_by_{}", idents.name);
+        let modifier_name = format_ident!("modify_by_{}", idents.name);
+
+        quote! {
+            #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Vec<&#element_name #types>
+            where
+                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized,
+            {
+                match self.#index_name.get(key) {
+                    Some(bitmap) => bitmap.iter().map(|idx| &self._store[idx as usize]).collect(),
+                    None => Vec::new(),
+                }
+            }
+
+            #field_vis fn #counter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> usize
+            where
+                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized,
+            {
+                self.#index_name.get(key).map_or(0, |bitmap| bitmap.len() as usize)
+            }
+
+            #field_vis fn #contains_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> bool
+            where
+                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized,
+            {
+                self.#index_name.contains_key(key)
+            }
+
+            #field_vis fn #distinct_counter_name(&self) -> usize {
+                self.#index_name.len()
+            }
+
+            #field_vis fn #bitmap_getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Option<&::multi_index_map::roaring::RoaringBitmap>
+            where
+                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized,
+            {
+                self.#index_name.get(key)
+            }
+
+            #field_vis fn #iter_getter_name(&self) -> impl ::std::iter::Iterator<Item = &#element_name #types> + '_ {
+                self.#index_name
+                    .values()
+                    .flat_map(|bitmap| bitmap.iter())
+                    .map(move |idx| &self._store[idx as usize])
+            }
+
+            #field_vis fn #remover_name(&mut self, key: &#field_type) -> Vec<#element_name #types> {
+                if let Some(bitmap) = self.#index_name.remove(key) {
+                    let mut elems = Vec::with_capacity(bitmap.len() as usize);
+                    for idx in bitmap {
+                        let idx = idx as usize;
+                        let elem_orig = self._store.remove(idx);
+                        #(#removes)*
+                        elems.push(elem_orig)
+                    }
+                    elems
+                } else {
+                    Vec::new()
+                }
+            }
+
+            #field_vis fn #modifier_name(
+                &mut self,
+                key: &#field_type,
+                mut f: impl FnMut(&mut #element_name #types)
+            ) -> Vec<&#element_name #types> {
+                let idxs = match self.#index_name.get(key) {
+                    Some(bitmap) => bitmap.iter().map(|idx| idx as usize).collect::<Vec<usize>>(),
+                    None => return Vec::new(),
+                };
+                let mut refs = Vec::with_capacity(idxs.len());
+                let idx_mut_iter = match self._store.pick_many_mut(&idxs) {
+                    Ok(mut_iter) => idxs.into_iter().zip(mut_iter.into_iter()),
+                    Err(e) => {
+                        panic!(
+                            "Error getting mutable reference of bitmap field `{0}` in modifier. detail: {1:?}",
+                            #field_name_str,
+                            e,
+                        );
+                    }
+                };
+                for (idx, elem) in idx_mut_iter {
+                    #(#pre_modifies)*
+                    f(elem);
+                    #(#post_modifies)*
+                    refs.push(&*elem);
+                }
+                refs
+            }
+        }
+    });
+
+    quote! {
+        // Iterate over the elements at the positions in the bitmap, which is usually the result of combining
+        //   the bitmaps returned by the bitmap_by_ methods. Positions without an element are skipped.
+        #element_vis fn iter_bitmap<'__mim_iter_lifetime>(
+            &'__mim_iter_lifetime self,
+            bitmap: &'__mim_iter_lifetime ::multi_index_map::roaring::RoaringBitmap,
+        ) -> impl ::std::iter::Iterator<Item = &'__mim_iter_lifetime #element_name #types> + '__mim_iter_lifetime {
+            bitmap.iter().filter_map(move |idx| self._store.get(idx as usize))
+        }
+
+        #(#accessors)*
+    }
+}
+
+// Generate an interval index, which records the half-open interval from the start field to the end field
+//   of each element in an interval tree, keyed on the type of those fields.
+pub(crate) fn generate_interval_index(
+    interval: &IntervalIndex,
+    idents: &FieldIdents,
+    key_type: &Type,
+) -> AuxiliaryIndex {
+    let start = &interval.start;
+    let end = &interval.end;
+    let index_name = &idents.index_name;
+    let orig_ident = &idents.cloned_name;
+
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            #index_name: ::multi_index_map::IntervalTree<#key_type>,
+        },
+        lookup_table_init: quote! {
+            #index_name: ::multi_index_map::IntervalTree::default(),
+        },
+        insert: quote! {
+            self.#index_name.insert(elem.#start.clone(), elem.#end.clone(), idx);
+        },
+        remove: quote! {
+            self.#index_name.remove(idx);
+        },
+        pre_modify: quote! {
+            let #orig_ident = (elem.#start.clone(), elem.#end.clone());
+        },
+        post_modify: quote! {
+            if elem.#start != #orig_ident.0 || elem.#end != #orig_ident.1 {
+                self.#index_name.remove(idx);
+                self.#index_name.insert(elem.#start.clone(), elem.#end.clone(), idx);
+            }
+        },
+        clear: quote! {
+            self.#index_name.clear();
+        },
+        reserve: quote! {
+            self.#index_name.reserve(additional);
+        },
+        shrink: quote! {
+            self.#index_name.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the methods accessing the interval indexes.
+// Both return the matching elements in order of the start of their interval.
+pub(crate) fn generate_interval_methods(
+    intervals: &[(IntervalIndex, FieldIdents, Type)],
+    element_name: &Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let accessors = intervals.iter().map(|(_, idents, key_type)| {
+        let index_name = &idents.index_name;
+        let overlapping_name = format_ident!("overlapping_{}", idents.name);
+        let containing_name = format_ident!("containing_{}", idents.name);
+
+        quote! {
+            // Get the elements whose interval overlaps the given half-open range.
+            #element_vis fn #overlapping_name(&self, range: ::std::ops::Range<#key_type>) -> Vec<&#element_name #types> {
+                self.#index_name
+                    .overlapping(&range.start, &range.end)
+                    .into_iter()
+                    .map(|idx| &self._store[idx])
+                    .collect()
+            }
+
+            // Get the elements whose interval contains the given point.
+            #element_vis fn #containing_name(&self, point: &#key_type) -> Vec<&#element_name #types> {
+                self.#index_name
+                    .containing(point)
+                    .into_iter()
+                    .map(|idx| &self._store[idx])
+                    .collect()
+            }
+        }
+    });
+
+    quote! {
+        #(#accessors)*
+    }
+}
+
+// Generate the order statistics of a ranked index, kept alongside the field's ordinary lookup table,
+//   as a tree of keys recording the size of each subtree.
+// The tree stores the current key of each element, so no clone is needed before a modification.
+pub(crate) fn generate_ranked_index(field: &Field, idents: &FieldIdents) -> AuxiliaryIndex {
+    let field_name = &idents.name;
+    let field_type = &field.ty;
+    let rank_index_name = format_ident!("_{}_rank_index", field_name);
+
+    AuxiliaryIndex {
+        lookup_table_field: quote! {
+            #rank_index_name: ::multi_index_map::RankTree<#field_type>,
+        },
+        lookup_table_init: quote! {
+            #rank_index_name: ::multi_index_map::RankTree::default(),
+        },
+        insert: quote! {
+            self.#rank_index_name.insert(elem.#field_name.clone(), idx);
+        },
+        remove: quote! {
+            self.#rank_index_name.remove(idx);
+        },
+        pre_modify: quote! {},
+        post_modify: quote! {
+            if self.#rank_index_name.key(idx) != &elem.#field_name {
+                self.#rank_index_name.remove(idx);
+                self.#rank_index_name.insert(elem.#field_name.clone(), idx);
+            }
+        },
+        clear: quote! {
+            self.#rank_index_name.clear();
+        },
+        reserve: quote! {
+            self.#rank_index_name.reserve(additional);
+        },
+        shrink: quote! {
+            self.#rank_index_name.shrink_to_fit();
+        },
+    }
+}
+
+// Generate the rank_by_ and nth_by_ methods of the ranked indexes.
+// Elements with equal keys are ordered by their position in the backing storage,
+//   the same order they are returned in by the accessors of a non-unique index.
+pub(crate) fn generate_ranked_methods(
+    ranked_fields: &[(Field, FieldIdents)],
+    element_name: &Ident,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let accessors = ranked_fields.iter().map(|(field, idents)| {
+        let field_vis = &field.vis;
+        let field_type = &field.ty;
+        let rank_index_name = format_ident!("_{}_rank_index", idents.name);
+        let rank_name = format_ident!("rank_by_{}", idents.name);
+        let nth_name = format_ident!("nth_by_{}", idents.name);
+
+        quote! {
+            // Get the number of elements whose key is less than the given key.
+            #field_vis fn #rank_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> usize
+            where
+                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+                __MultiIndexMapKeyType: Ord + ?Sized,
+            {
+                self.#rank_index_name.rank(key)
+            }
+
+            // Get the element at the given position in key order, counting from zero.
+            #field_vis fn #nth_name(&self, n: usize) -> Option<&#element_name #types> {
+                self.#rank_index_name.nth(n).map(|idx| &self._store[idx])
+            }
+        }
+    });
+
+    quote! {
+        #(#accessors)*
+    }
+}
+
+// Generate the private method finding every unique index on which the element bound to `elem`
+//   shares a key with an element other than the one at `idx`, used to build a UniquenessError.
+// This is only run once a conflict is already known, so the lookups are not repeated on success.
+pub(crate) fn generate_uniqueness_conflicts(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let conflicts = indexed_fields
+        .iter()
+        .filter(|(.., uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(_, idents, _, _, key_source)| {
+            let index_name = &idents.index_name;
+            let index_name_string = idents.name.to_string();
+            let key = borrowed_key(&quote! { elem }, idents, key_source);
+            guard_key(
+                &quote! { elem },
+                idents,
+                key_source,
+                quote! {
+                    if let Some(other_idx) = self.#index_name.get(#key).copied() {
+                        if other_idx != idx {
+                            conflicts.push(::multi_index_map::UniquenessConflict::new(
+                                #index_name_string,
+                                ::multi_index_map::ElementId::from_index(other_idx),
+                            ));
+                        }
+                    }
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if conflicts.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        fn __mim_uniqueness_conflicts(
+            &self,
+            elem: &#element_name #types,
+            idx: usize
+        ) -> Vec<::multi_index_map::UniquenessConflict> {
+            let mut conflicts = Vec::new();
+            #(#conflicts)*
+            conflicts
+        }
+
+        fn __mim_uniqueness_error(
+            &self,
+            elem: #element_name #types,
+            idx: usize
+        ) -> ::multi_index_map::UniquenessError<#element_name #types> {
+            let conflicts = self.__mim_uniqueness_conflicts(&elem, idx);
+            ::multi_index_map::UniquenessError::new(elem, conflicts)
+        }
+    }
+}
+
+// Generate the private methods used by each try_modify_by_ accessor, to modify the element at a position,
+//   and to restore an element modified this way.
+// `protected_idents` are every field read by any index, these are cloned before the modification,
+//   then if the modified element shares a key with another element in any unique index,
+//   they are restored before any lookup table is changed, and an error returned.
+// Otherwise the lookup tables are updated as in modify_by_, and the clones returned,
+//   so that an all-or-nothing modification of several elements can restore them with __mim_restore_at.
+pub(crate) fn generate_try_modify_helpers(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
+    protected_idents: &[&Ident],
+    protected_types: &[&Type],
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    if indexed_fields.is_empty() {
+        return quote! {};
+    }
+
+    let (_, types, _) = generics.split_for_impl();
+    let positions = (0..protected_idents.len()).map(syn::Index::from);
+    let restores = quote! {
+        #(elem.#protected_idents = original.#positions;)*
+    };
+
+    // Without a unique index a modification can never conflict.
+    let check_conflicts = if indexed_fields
+        .iter()
+        .any(|(.., uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+    {
+        quote! {
+            let conflicts = self.__mim_uniqueness_conflicts(&self._store[idx], idx);
+            if !conflicts.is_empty() {
+                let elem = &mut self._store[idx];
+                #restores
+                return Err(::multi_index_map::UniquenessError::new((), conflicts));
+            }
+            let elem = &mut self._store[idx];
+        }
+    } else {
+        quote! {}
+    };
+
+    // Only non-unique try_modify_by_ accessors modify several elements, so may need to restore them.
+    let restore_at = if indexed_fields
+        .iter()
+        .any(|(.., uniqueness, _)| matches!(uniqueness, Uniqueness::NonUnique))
+    {
+        quote! {
+            fn __mim_restore_at(&mut self, idx: usize, original: (#(#protected_types,)*)) {
+                let elem = &mut self._store[idx];
+                #(#pre_modifies)*
+                #restores
+                #(#post_modifies)*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        fn __mim_try_modify_at(
+            &mut self,
+            idx: usize,
+            f: impl FnOnce(&mut #element_name #types)
+        ) -> Result<(#(#protected_types,)*), ::multi_index_map::UniquenessError<()>> {
+            let elem = &mut self._store[idx];
+            let original = (#(elem.#protected_idents.clone(),)*);
+            #(#pre_modifies)*
+            f(elem);
+            #check_conflicts
+            #(#post_modifies)*
+            Ok(original)
+        }
+
+        #restore_at
+    }
+}
+
+// Generate the insert_or_replace method, which first removes every element sharing a key with the new element
+//   in any unique index, then inserts the new element, returning the removed elements.
+// Removed elements are returned in the order of the first index they conflicted on, each only once.
+pub(crate) fn generate_insert_or_replace(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let conflicts = indexed_fields
+        .iter()
+        .filter(|(.., uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(_, idents, _, _, key_source)| {
+            let index_name = &idents.index_name;
+            let key = borrowed_key(&quote! { elem }, idents, key_source);
+            guard_key(
+                &quote! { elem },
+                idents,
+                key_source,
+                quote! {
+                    if let Some(idx) = self.#index_name.get(#key) {
+                        if !idxs.contains(idx) {
+                            idxs.push(*idx);
+                        }
+                    }
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if conflicts.is_empty() {
+        return quote! {
+            #element_vis fn insert_or_replace(&mut self, elem: #element_name #types) -> Vec<#element_name #types> {
+                self.insert(elem);
+                Vec::new()
+            }
+        };
+    }
+
+    quote! {
+        #element_vis fn insert_or_replace(&mut self, elem: #element_name #types) -> Vec<#element_name #types> {
+            let mut idxs = Vec::new();
+            #(#conflicts)*
+
+            let mut elems = Vec::with_capacity(idxs.len());
+            for idx in idxs {
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                elems.push(elem_orig);
+            }
+
+            self.try_insert(elem).expect(
+                "Internal invariants broken, uniqueness violated despite removing every conflicting element"
+            );
+            elems
+        }
+    }
+}
+
+// Generate try_extend, which inserts a batch of elements only if none of them violate a uniqueness constraint.
+// Every element is checked against each unique index, and against the keys of the earlier elements of the batch,
+//   which are collected in a temporary map per index from each key to its latest position, before any element is inserted.
+// Elements of the batch sharing a key are all reported, not just the later ones.
+pub(crate) fn generate_try_extend(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    element_name: &Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let unique_fields = indexed_fields
+        .iter()
+        .filter(|(.., uniqueness, _)| matches!(uniqueness, Uniqueness::Unique))
+        .collect::<Vec<_>>();
+
+    let seen_inits = unique_fields.iter().map(|(_, idents, ordering, ..)| {
+        let seen_name = format_ident!("{}_seen", idents.name);
+        match ordering {
+            Ordering::Hashed => quote! {
+                let mut #seen_name = ::std::collections::HashMap::new();
+            },
+            Ordering::Ordered => quote! {
+                let mut #seen_name = ::std::collections::BTreeMap::new();
+            },
+        }
+    });
+
+    let checks = unique_fields
+        .iter()
+        .map(|(_, idents, _, _, key_source)| {
+            let index_name = &idents.index_name;
+            let seen_name = format_ident!("{}_seen", idents.name);
+            let key = borrowed_key(&quote! { elem }, idents, key_source);
+            let seen_key = owned_key(&quote! { elem }, idents, key_source);
+            guard_key(
+                &quote! { elem },
+                idents,
+                key_source,
+                quote! {
+                    if let Some(earlier) = #seen_name.insert(#seen_key, position) {
+                        conflicts.push(earlier);
+                        conflicting = true;
+                    }
+                    if self.#index_name.contains_key(#key) {
+                        conflicting = true;
+                    }
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let validation = if checks.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            let mut conflicts = Vec::new();
+            #(#seen_inits)*
+            for (position, elem) in elements.iter().enumerate() {
+                let mut conflicting = false;
+                #(#checks)*
+                if conflicting {
+                    conflicts.push(position);
+                }
+            }
+            if !conflicts.is_empty() {
+                conflicts.sort_unstable();
+                conflicts.dedup();
+                return Err(::multi_index_map::BatchError { elements, conflicts });
+            }
+        }
+    };
+
+    quote! {
+        // Insert every element of the batch, or none of them if any would violate a uniqueness constraint,
+        //   returning the ids of the inserted elements in the order of the batch.
+        #element_vis fn try_extend(
+            &mut self,
+            iter: impl ::std::iter::IntoIterator<Item = #element_name #types>
+        ) -> Result<Vec<::multi_index_map::ElementId>, ::multi_index_map::BatchError<#element_name #types>> {
+            let elements = iter.into_iter().collect::<Vec<_>>();
+            #validation
+
+            self.reserve(elements.len());
+            Ok(elements
+                .into_iter()
+                .map(|elem| {
+                    self.try_insert(elem).expect(
+                        "Internal invariants broken, uniqueness violated despite validating the batch"
+                    )
+                })
+                .collect())
+        }
+    }
+}
+
+// Generate the methods accessing an element by the ElementId returned when it was inserted,
+//   which is its position in the backing storage, so these need no lookup table.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_element_id_methods(
+    element_name: &Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+    removes: &[proc_macro2::TokenStream],
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    quote! {
+        #element_vis fn get_by_element_id(&self, id: ::multi_index_map::ElementId) -> Option<&#element_name #types> {
+            self._store.get(id.index())
+        }
+
+        #element_vis fn contains_element_id(&self, id: ::multi_index_map::ElementId) -> bool {
+            self._store.contains(id.index())
+        }
+
+        // Iterate over the backing storage, as with iter, but with the ElementId of each element.
+        #element_vis fn iter_with_ids(&self) -> impl Iterator<Item = (::multi_index_map::ElementId, &#element_name #types)> + '_ {
+            self._store
+                .iter()
+                .map(|(idx, elem)| (::multi_index_map::ElementId::from_index(idx), elem))
+        }
+
+        #element_vis fn update_by_element_id(
+            &mut self,
+            id: ::multi_index_map::ElementId,
+            f: impl FnOnce(#(&mut #unindexed_types,)*)
+        ) -> Option<&#element_name #types> {
+            let elem = self._store.get_mut(id.index())?;
+            f(#(&mut elem.#unindexed_idents,)*);
+            Some(elem)
+        }
+
+        #element_vis fn modify_by_element_id(
+            &mut self,
+            id: ::multi_index_map::ElementId,
+            f: impl FnOnce(&mut #element_name #types)
+        ) -> Option<&#element_name #types> {
+            let idx = id.index();
+            let elem = self._store.get_mut(idx)?;
+            #(#pre_modifies)*
+            f(elem);
+            #(#post_modifies)*
+            Some(elem)
+        }
+
+        #element_vis fn remove_by_element_id(&mut self, id: ::multi_index_map::ElementId) -> Option<#element_name #types> {
+            let idx = id.index();
+            let elem_orig = self._store.try_remove(idx)?;
+            #(#removes)*
+            Some(elem_orig)
+        }
+    }
+}
+
+// Generate the retain and extract_if methods, which remove every element matching a predicate.
+// The positions to remove are found in a single pass over the backing storage,
+//   then each element is removed from the backing storage and every lookup table, in order of position.
+// This removal is shared with the drain_by_ accessors, which find positions in index order,
+//   and must not be generic over the key type, as that would prevent inferring the key type of each lookup table.
+pub(crate) fn generate_retain_methods(
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    quote! {
+        // Keep only the elements for which the predicate returns true.
+        #element_vis fn retain(&mut self, mut f: impl FnMut(&#element_name #types) -> bool) {
+            self.extract_if(|elem| !f(elem));
+        }
+
+        // Remove and return the elements for which the predicate returns true.
+        #element_vis fn extract_if(&mut self, mut f: impl FnMut(&#element_name #types) -> bool) -> Vec<#element_name #types> {
+            let idxs = self
+                ._store
+                .iter()
+                .filter(|(_, elem)| f(elem))
+                .map(|(idx, _)| idx)
+                .collect::<Vec<usize>>();
+            self.__mim_remove_all_at(idxs)
+        }
+
+        fn __mim_remove_all_at(&mut self, idxs: Vec<usize>) -> Vec<#element_name #types> {
+            let mut elems = Vec::with_capacity(idxs.len());
+            for idx in idxs {
+                // An element of an each index may be under several keys, so is only removed at the first.
+                let Some(elem_orig) = self._store.try_remove(idx) else {
+                    continue;
+                };
+                #(#removes)*
+                elems.push(elem_orig);
+            }
+            elems
+        }
+    }
+}
+
+// Generate the entry_by_ methods of each unique index, and the entry types they return, as a pair.
+// Occupied entries record the position of their element in the backing storage,
+//   so modifying or removing through them does not look up the key again.
+// Vacant entries insert through try_insert, so the other unique indexes are still checked.
+// The key of a vacant entry is written into the element it inserts, so the element is always inserted under that key.
+// A computed key cannot be written into the element, so key extractor indexes have no entries.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_entries(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    map_name: &Ident,
+    element_name: &Ident,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+    removes: &[proc_macro2::TokenStream],
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    generics: &Generics,
+    entry_generics: &Generics,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (_, types, _) = generics.split_for_impl();
+    let (entry_impls, entry_types, entry_where_clause) = entry_generics.split_for_impl();
+
+    let unique_fields = indexed_fields
+        .iter()
+        .filter(|(.., uniqueness, key_source)| {
+            matches!(uniqueness, Uniqueness::Unique)
+                && !matches!(key_source, KeySource::Extractor(_))
+        })
+        .collect::<Vec<_>>();
+    if unique_fields.is_empty() {
+        return (quote! {}, quote! {});
+    }
+
+    let methods = unique_fields.iter().map(|(f, idents, ..)| {
+        let field_vis = &f.vis;
+        let field_type = &f.ty;
+        let index_name = &idents.index_name;
+        let entry_getter_name = format_ident!("entry_by_{}", idents.name);
+        let entry_name = &idents.entry_name;
+        let occupied_entry_name = &idents.occupied_entry_name;
+        let vacant_entry_name = &idents.vacant_entry_name;
+
+        quote! {
+            #field_vis fn #entry_getter_name<'__mim_entry_lifetime>(&'__mim_entry_lifetime mut self, key: #field_type) -> #entry_name #entry_types {
+                match self.#index_name.get(&key).copied() {
+                    Some(idx) => #entry_name::Occupied(#occupied_entry_name { map: self, idx }),
+                    None => #entry_name::Vacant(#vacant_entry_name { map: self, key }),
+                }
+            }
+        }
+    });
+
+    let entry_types_defs = unique_fields.iter().map(|(f, idents, _, _, key_source)| {
+        let field_vis = &f.vis;
+        let field_type = &f.ty;
+        let entry_name = &idents.entry_name;
+        let occupied_entry_name = &idents.occupied_entry_name;
+        let vacant_entry_name = &idents.vacant_entry_name;
+        let field_name = &idents.name;
+        let set_key = match key_source {
+            KeySource::OptionalField => quote! { elem.#field_name = Some(self.key); },
+            KeySource::Composite(fields) => {
+                let positions = (0..fields.len()).map(syn::Index::from);
+                quote! { #(elem.#fields = self.key.#positions;)* }
+            }
+            _ => quote! { elem.#field_name = self.key; },
+        };
+
+        quote! {
+            #field_vis enum #entry_name #entry_impls #entry_where_clause {
+                Occupied(#occupied_entry_name #entry_types),
+                Vacant(#vacant_entry_name #entry_types),
+            }
+
+            #field_vis struct #occupied_entry_name #entry_impls #entry_where_clause {
+                map: &'__mim_entry_lifetime mut #map_name #types,
+                idx: usize,
+            }
+
+            #field_vis struct #vacant_entry_name #entry_impls #entry_where_clause {
+                map: &'__mim_entry_lifetime mut #map_name #types,
+                key: #field_type,
+            }
+
+            impl #entry_impls #occupied_entry_name #entry_types #entry_where_clause {
+                #field_vis fn get(&self) -> &#element_name #types {
+                    &self.map._store[self.idx]
+                }
+
+                #field_vis fn into_ref(self) -> &'__mim_entry_lifetime #element_name #types {
+                    &self.map._store[self.idx]
+                }
+
+                // Modify only the unindexed fields, as with update_by_, so no lookup tables are changed.
+                #field_vis fn update(
+                    self,
+                    f: impl FnOnce(#(&mut #unindexed_types,)*)
+                ) -> &'__mim_entry_lifetime #element_name #types {
+                    let elem = &mut self.map._store[self.idx];
+                    f(#(&mut elem.#unindexed_idents,)*);
+                    elem
+                }
+
+                // Modify the element, updating every index, as with modify_by_.
+                #field_vis fn modify(
+                    self,
+                    f: impl FnOnce(&mut #element_name #types)
+                ) -> &'__mim_entry_lifetime #element_name #types {
+                    self.map.__mim_modify_at(self.idx, f)
+                }
+
+                #field_vis fn remove(self) -> #element_name #types {
+                    self.map.__mim_remove_at(self.idx)
+                }
+            }
+
+            impl #entry_impls #vacant_entry_name #entry_types #entry_where_clause {
+                #field_vis fn key(&self) -> &#field_type {
+                    &self.key
+                }
+
+                #field_vis fn into_key(self) -> #field_type {
+                    self.key
+                }
+
+                // The key of this entry replaces the key of the element, so only its other fields are used.
+                // Fails if the element would violate the uniqueness of any other index, as with try_insert.
+                #field_vis fn insert(
+                    self,
+                    mut elem: #element_name #types
+                ) -> Result<&'__mim_entry_lifetime #element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+                    #set_key
+                    let id = self.map.try_insert(elem)?;
+                    Ok(&self.map._store[id.index()])
+                }
+            }
+        }
+    });
+
+    (
+        quote! {
+            fn __mim_modify_at(
+                &mut self,
+                idx: usize,
+                f: impl FnOnce(&mut #element_name #types)
+            ) -> &#element_name #types {
+                let elem = &mut self._store[idx];
+                #(#pre_modifies)*
+                f(elem);
+                #(#post_modifies)*
+                elem
+            }
+
+            fn __mim_remove_at(&mut self, idx: usize) -> #element_name #types {
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                elem_orig
+            }
+
+            #(#methods)*
+        },
+        quote! {
+            #(#entry_types_defs)*
+        },
+    )
+}
+
+// Generate the transaction method, and the transaction type it passes to its closure, as a pair.
+// The transaction logs the position of each element it changes, alongside the element beforehand if there was one,
+//   then undoes the changes in reverse order if it is dropped without being committed.
+// Changes are made through the methods of the map, so every lookup table is kept up to date.
+// Undoing in reverse order restores each removed element to its original position,
+//   as the backing storage always reuses the most recently vacated position first, which the rollback asserts.
+pub(crate) fn generate_transaction(
+    indexed_fields: &[(Field, FieldIdents, Ordering, Uniqueness, KeySource)],
+    map_name: &Ident,
+    element_name: &Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    generics: &Generics,
+    transaction_generics: &Generics,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (_, types, _) = generics.split_for_impl();
+    let mut transaction_generics = transaction_generics.clone();
+    transaction_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#element_name #types: Clone));
+    let (transaction_impls, transaction_types, transaction_where_clause) =
+        transaction_generics.split_for_impl();
+    let transaction_name = format_ident!("{}Transaction", map_name);
+    let error_msg = "Internal invariants broken, unable to find element in backing storage despite being logged by the transaction";
+    let restore_msg =
+        "Internal invariants broken, unable to restore removed element to its original position";
+
+    let field_methods = indexed_fields.iter().map(|(f, idents, _, uniqueness, _)| {
+        let field_vis = &f.vis;
+        let field_type = &f.ty;
+        let index_name = &idents.index_name;
+        let remover_name = format_ident!("remove_by_{}", idents.name);
+        let try_modifier_name = format_ident!("try_modify_by_{}", idents.name);
+        let modifier_name = format_ident!("modify_by_{}", idents.name);
+        let updater_name = format_ident!("update_by_{}", idents.name);
+
+        match uniqueness {
+            Uniqueness::Unique => quote! {
+                #field_vis fn #remover_name(&mut self, key: &#field_type) -> Option<#element_name #types> {
+                    let idx = self.map.#index_name.get(key).copied()?;
+                    Some(self.__mim_remove_at(idx))
+                }
+
+                // Only the unindexed fields are changed, so the element is logged but no lookup table is touched.
+                #field_vis fn #updater_name(
+                    &mut self,
+                    key: &#field_type,
+                    f: impl FnOnce(#(&mut #unindexed_types,)*)
+                ) -> Option<&#element_name #types> {
+                    let idx = self.map.#index_name.get(key).copied()?;
+                    self.undo_log.push((idx, Some(self.map._store[idx].clone())));
+                    self.map.update_by_element_id(::multi_index_map::ElementId::from_index(idx), f)
+                }
+
+                // As with the map, violating a uniqueness constraint panics,
+                //   which undoes the whole transaction as the panic unwinds through it.
+                #field_vis fn #modifier_name(
+                    &mut self,
+                    key: &#field_type,
+                    f: impl FnOnce(&mut #element_name #types)
+                ) -> Option<&#element_name #types> {
+                    self.#try_modifier_name(key, f).expect("Unable to modify element")
+                }
+
+                #field_vis fn #try_modifier_name(
+                    &mut self,
+                    key: &#field_type,
+                    f: impl FnOnce(&mut #element_name #types)
+                ) -> Result<Option<&#element_name #types>, ::multi_index_map::UniquenessError<()>> {
+                    let Some(idx) = self.map.#index_name.get(key).copied() else {
+                        return Ok(None);
+                    };
+                    self.__mim_try_modify_at(idx, f)?;
+                    Ok(Some(&self.map._store[idx]))
+                }
+            },
+            Uniqueness::NonUnique => quote! {
+                #field_vis fn #remover_name(&mut self, key: &#field_type) -> Vec<#element_name #types> {
+                    let idxs = match self.map.#index_name.get(key) {
+                        Some(container) => container.iter().copied().collect::<Vec<usize>>(),
+                        None => Vec::new(),
+                    };
+                    idxs.into_iter().map(|idx| self.__mim_remove_at(idx)).collect()
+                }
+
+                #field_vis fn #updater_name(
+                    &mut self,
+                    key: &#field_type,
+                    mut f: impl FnMut(#(&mut #unindexed_types,)*)
+                ) -> Vec<&#element_name #types> {
+                    let idxs = match self.map.#index_name.get(key) {
+                        Some(container) => container.iter().copied().collect::<Vec<usize>>(),
+                        None => Vec::new(),
+                    };
+                    for idx in idxs.iter().copied() {
+                        self.undo_log.push((idx, Some(self.map._store[idx].clone())));
+                    }
+                    for idx in idxs.iter().copied() {
+                        self.map.update_by_element_id(::multi_index_map::ElementId::from_index(idx), &mut f);
+                    }
+                    idxs.into_iter().map(|idx| &self.map._store[idx]).collect()
+                }
+
+                #field_vis fn #modifier_name(
+                    &mut self,
+                    key: &#field_type,
+                    f: impl FnMut(&mut #element_name #types)
+                ) -> Vec<&#element_name #types> {
+                    self.#try_modifier_name(key, f).expect("Unable to modify element")
+                }
+
+                // Modify every element with the key, or none of them if any would violate a uniqueness constraint.
+                #field_vis fn #try_modifier_name(
+                    &mut self,
+                    key: &#field_type,
+                    mut f: impl FnMut(&mut #element_name #types)
+                ) -> Result<Vec<&#element_name #types>, ::multi_index_map::UniquenessError<()>> {
+                    let idxs = match self.map.#index_name.get(key) {
+                        Some(container) => container.iter().copied().collect::<Vec<usize>>(),
+                        None => Vec::new(),
+                    };
+                    let undo_len = self.undo_log.len();
+                    for idx in idxs.iter().copied() {
+                        if let Err(e) = self.__mim_try_modify_at(idx, &mut f) {
+                            self.__mim_rollback_to(undo_len);
+                            return Err(e);
+                        }
+                    }
+                    Ok(idxs.into_iter().map(|idx| &self.map._store[idx]).collect())
+                }
+            },
+        }
+    });
+
+    // Modifying through the map checks the unique indexes, which requires there to be some index.
+    let try_modify_at = if indexed_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn __mim_try_modify_at(
+                &mut self,
+                idx: usize,
+                f: impl FnOnce(&mut #element_name #types)
+            ) -> Result<(), ::multi_index_map::UniquenessError<()>> {
+                self.undo_log.push((idx, Some(self.map._store[idx].clone())));
+                self.modifying = true;
+                let result = self.map.__mim_try_modify_at(idx, f);
+                self.modifying = false;
+                result?;
+                Ok(())
+            }
+        }
+    };
+
+    let method = quote! {
+        // Run the closure with a transaction over the map, if it returns an error, or panics,
+        //   every change made through the transaction is undone before returning.
+        #element_vis fn transaction<'__mim_transaction_lifetime, __MultiIndexMapOkType, __MultiIndexMapErrType>(
+            &'__mim_transaction_lifetime mut self,
+            f: impl FnOnce(&mut #transaction_name #transaction_types) -> Result<__MultiIndexMapOkType, __MultiIndexMapErrType>
+        ) -> Result<__MultiIndexMapOkType, __MultiIndexMapErrType>
+        where
+            #element_name #types: Clone,
+        {
+            let mut transaction = #transaction_name {
+                map: self,
+                undo_log: Vec::new(),
+                modifying: false,
+            };
+            let result = f(&mut transaction);
+            if result.is_ok() {
+                transaction.undo_log.clear();
+            }
+            result
+        }
+    };
+
+    let transaction_type = quote! {
+        // The elements of the map can be read through the transaction, but only changed through its own methods,
+        //   so that every change is logged.
+        #element_vis struct #transaction_name #transaction_impls #transaction_where_clause {
+            map: &'__mim_transaction_lifetime mut #map_name #types,
+            // The position of each changed element, and the element beforehand if there was one.
+            undo_log: Vec<(usize, Option<#element_name #types>)>,
+            // Set while a closure is modifying the most recently logged element.
+            // If the closure panics no lookup table has been updated yet, so they still hold the keys of the logged element.
+            modifying: bool,
+        }
+
+        impl #transaction_impls ::std::ops::Deref for #transaction_name #transaction_types #transaction_where_clause {
+            type Target = #map_name #types;
+
+            fn deref(&self) -> &Self::Target {
+                self.map
+            }
+        }
+
+        impl #transaction_impls Drop for #transaction_name #transaction_types #transaction_where_clause {
+            fn drop(&mut self) {
+                self.__mim_rollback_to(0);
+            }
+        }
+
+        impl #transaction_impls #transaction_name #transaction_types #transaction_where_clause {
+            #element_vis fn try_insert(
+                &mut self,
+                elem: #element_name #types
+            ) -> Result<::multi_index_map::ElementId, ::multi_index_map::UniquenessError<#element_name #types>> {
+                let id = self.map.try_insert(elem)?;
+                self.undo_log.push((id.index(), None));
+                Ok(id)
+            }
+
+            #element_vis fn insert(&mut self, elem: #element_name #types) -> ::multi_index_map::ElementId {
+                self.try_insert(elem).expect("Unable to insert element")
+            }
+
+            #element_vis fn remove_by_element_id(&mut self, id: ::multi_index_map::ElementId) -> Option<#element_name #types> {
+                if !self.map.contains_element_id(id) {
+                    return None;
+                }
+                Some(self.__mim_remove_at(id.index()))
+            }
+
+            #(#field_methods)*
+
+            fn __mim_remove_at(&mut self, idx: usize) -> #element_name #types {
+                let elem = self
+                    .map
+                    .remove_by_element_id(::multi_index_map::ElementId::from_index(idx))
+                    .expect(#error_msg);
+                self.undo_log.push((idx, Some(elem.clone())));
+                elem
+            }
+
+            #try_modify_at
+
+            // Undo the changes logged after the first `undo_len`, most recent first.
+            // Each step can only fail if the map's invariants were broken, in which case it panics rather than
+            //   leaving the map partly restored, even though that aborts if the closure of the transaction is panicking.
+            fn __mim_rollback_to(&mut self, undo_len: usize) {
+                while self.undo_log.len() > undo_len {
+                    let Some((idx, original)) = self.undo_log.pop() else {
+                        break;
+                    };
+                    let id = ::multi_index_map::ElementId::from_index(idx);
+                    let interrupted = ::std::mem::take(&mut self.modifying);
+                    match original {
+                        None => {
+                            self.map.remove_by_element_id(id).expect(#error_msg);
+                        }
+                        // The lookup tables still hold the keys of the original,
+                        //   so only the element itself is put back, rather than reindexed.
+                        Some(original) if interrupted => {
+                            *self.map._store.get_mut(idx).expect(#error_msg) = original;
+                        }
+                        Some(original) if self.map.contains_element_id(id) => {
+                            self.map.modify_by_element_id(id, |elem| *elem = original);
+                        }
+                        // Every change logged after this removal has already been undone,
+                        //   so its position is the most recently vacated one, which the backing storage fills next.
+                        Some(original) => {
+                            assert_eq!(self.map._store.vacant_key(), idx, #restore_msg);
+                            let Ok(restored) = self.map.try_insert(original) else {
+                                panic!(#restore_msg);
+                            };
+                            assert_eq!(restored.index(), idx, #restore_msg);
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    (method, transaction_type)
+}
+
+// Build the final output using quasi-quoting
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_expanded(
+    extra_attrs: &ExtraAttributes,
+    generics: &Generics,
+    map_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    entries_for_insert: impl Iterator<Item = proc_macro2::TokenStream>,
+    inserts_for_entries: impl Iterator<Item = proc_macro2::TokenStream>,
+    accessors: impl Iterator<Item = proc_macro2::TokenStream>,
+    iterators: impl Iterator<Item = proc_macro2::TokenStream>,
+    clears: &[proc_macro2::TokenStream],
+    lookup_table_fields: impl Iterator<Item = proc_macro2::TokenStream>,
+    lookup_table_fields_init: impl Iterator<Item = proc_macro2::TokenStream>,
+    lookup_table_fields_default: impl Iterator<Item = proc_macro2::TokenStream>,
+    lookup_table_fields_shrink: impl Iterator<Item = proc_macro2::TokenStream>,
+    lookup_table_fields_reserve: impl Iterator<Item = proc_macro2::TokenStream>,
+    iter_mut_name: &proc_macro2::Ident,
+    iter_mut: proc_macro2::TokenStream,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let derives = &extra_attrs.derives;
+    let (impls, types, where_clause) = generics.split_for_impl();
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    quote! {
+        #(#[#derives])*
+        #element_vis struct #map_name #impls {
+            _store: ::multi_index_map::slab::Slab<#element_name #types>,
+            #(#lookup_table_fields)*
+        }
+
+        impl #impls Default for #map_name #types #where_clause {
+            fn default() -> Self {
+                Self {
+                    _store: ::multi_index_map::slab::Slab::default(),
+                    #(#lookup_table_fields_default)*
+                }
+            }
+        }
+
+        impl #impls #map_name #types #where_clause {
+            #element_vis fn with_capacity(n: usize) -> Self {
+                Self {
+                    _store: ::multi_index_map::slab::Slab::with_capacity(n),
+                    #(#lookup_table_fields_init)*
+                }
+            }
+
+            #element_vis fn capacity(&self) -> usize {
+                self._store.capacity()
+            }
+
+            #element_vis fn len(&self) -> usize {
+                self._store.len()
+            }
+
+            #element_vis fn is_empty(&self) -> bool {
+                self._store.is_empty()
+            }
+
+            // reserving is slow. users are in control of when to reserve
+            #element_vis fn reserve(&mut self, additional: usize) {
+                self._store.reserve(additional);
+                #(#lookup_table_fields_reserve)*
+            }
+
+            // shrinking is slow. users are in control of when to shrink
             #element_vis fn shrink_to_fit(&mut self) {
                 self._store.shrink_to_fit();
                 #(#lookup_table_fields_shrink)*
             }
 
-            #element_vis fn try_insert(&mut self, elem: #element_name #types) -> Result<&#element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+            #element_vis fn try_insert(&mut self, elem: #element_name #types) -> Result<::multi_index_map::ElementId, ::multi_index_map::UniquenessError<#element_name #types>> {
                 let store_entry = self._store.vacant_entry();
                 let idx = store_entry.key();
 
                 #(#entries_for_insert)*
                 #(#inserts_for_entries)*
 
-                let elem = store_entry.insert(elem);
+                store_entry.insert(elem);
 
-                Ok(elem)
+                Ok(::multi_index_map::ElementId::from_index(idx))
             }
 
-            #element_vis fn insert(&mut self, elem: #element_name #types) -> &#element_name #types {
+            #element_vis fn insert(&mut self, elem: #element_name #types) -> ::multi_index_map::ElementId {
                 self.try_insert(elem).expect("Unable to insert element")
             }
 
@@ -1036,6 +3410,13 @@ pub(crate) fn generate_expanded(
                 #(#clears)*
             }
 
+            // Remove every element, in order of their position in the backing storage.
+            // The lookup tables are cleared immediately, and every element is removed even if the iterator is dropped.
+            #element_vis fn drain(&mut self) -> ::multi_index_map::slab::Drain<'_, #element_name #types> {
+                #(#clears)*
+                self._store.drain()
+            }
+
             // Allow iteration directly over the backing storage
             #element_vis fn iter(&self) -> ::multi_index_map::slab::Iter<#element_name #types> {
                 self._store.iter()
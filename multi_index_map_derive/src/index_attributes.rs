@@ -2,7 +2,7 @@ use ::syn::Field;
 use proc_macro2::Span;
 use proc_macro_error2::emit_error;
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, DeriveInput, Meta, MetaList, NestedMeta, Path,
+    punctuated::Punctuated, spanned::Spanned, DeriveInput, Meta, MetaList, NestedMeta, Path, Type,
 };
 
 // Represents whether the index is Ordered or Hashed, ie. whether we use a BTreeMap or a FxHashMap
@@ -21,8 +21,59 @@ pub(crate) enum Uniqueness {
     NonUnique,
 }
 
-// Get the Ordering and Uniqueness for a given field attribute.
-pub(crate) fn get_index_kind(f: &Field) -> Option<(Ordering, Uniqueness)> {
+// Represents where the key of an index is read from in each element.
+pub(crate) enum KeySource {
+    // The index is declared on a single field with `#[multi_index(..)]`, and keyed on that field.
+    Field,
+    // The index is declared on a single field of type `Option<T>` with `#[multi_index(.., skip_none)]`,
+    //   and keyed on the value inside the Option, elements where the field is None are not in the index.
+    OptionalField,
+    // The index is declared on a single collection field with `#[multi_index(.., each)]`,
+    //   and keyed on each item in the collection, so an element may have any number of keys.
+    Each,
+    // The index is declared on the struct with `#[multi_index_composite(..)]`,
+    //   and keyed on a tuple of the listed fields, in the order they were listed.
+    Composite(Vec<syn::Ident>),
+    // The index is declared on the struct with `#[multi_index_key(..)]`,
+    //   and keyed on the value returned by calling this function with a reference to the element.
+    Extractor(Path),
+}
+
+// Represents an index declared at the struct level rather than on a single field, either
+//   `#[multi_index_composite(trader_ts(ordered_unique, fields(trader_name, timestamp)))]` or
+//   `#[multi_index_key(prefix(hashed_non_unique, fn = "Order::prefix", ty = "String"))]`
+// `fields` are the fields a composite key is read from, these must be protected from mutation outside of modify_by_.
+// The fields a key extractor reads are not known, so every field is protected when there is one.
+// `key_type` is only set for key extractor indexes, composite indexes are keyed on a tuple of the `fields`.
+pub(crate) struct StructIndex {
+    pub(crate) name: syn::Ident,
+    pub(crate) fields: Vec<syn::Ident>,
+    pub(crate) key_type: Option<Type>,
+    pub(crate) ordering: Ordering,
+    pub(crate) uniqueness: Uniqueness,
+    pub(crate) key_source: KeySource,
+}
+
+// Get the Ordering and Uniqueness from the path naming an index kind, eg. `hashed_unique`.
+fn parse_index_kind(nested_path: &Path) -> Option<(Ordering, Uniqueness)> {
+    if nested_path.is_ident("hashed_unique") {
+        Some((Ordering::Hashed, Uniqueness::Unique))
+    } else if nested_path.is_ident("ordered_unique") {
+        Some((Ordering::Ordered, Uniqueness::Unique))
+    } else if nested_path.is_ident("hashed_non_unique") {
+        Some((Ordering::Hashed, Uniqueness::NonUnique))
+    } else if nested_path.is_ident("ordered_non_unique") {
+        Some((Ordering::Ordered, Uniqueness::NonUnique))
+    } else {
+        emit_error!(nested_path.span(), "Invalid multi_index attribute, should be one of [hashed_unique, ordered_unique, hashed_non_unique, ordered_non_unique]");
+        None
+    }
+}
+
+// Get the Ordering, Uniqueness, and KeySource for a given field attribute.
+// The index kind may be followed by a modifier changing how the key is read from the field, eg. `skip_none`,
+//   or `ranked`, which leaves the key alone and is found by `is_ranked_index`.
+pub(crate) fn get_index_kind(f: &Field) -> Option<(Ordering, Uniqueness, KeySource)> {
     for attr in f.attrs.iter() {
         if attr.path.is_ident("multi_index") {
             let meta_list = match attr.parse_meta() {
@@ -35,26 +86,378 @@ pub(crate) fn get_index_kind(f: &Field) -> Option<(Ordering, Uniqueness)> {
                 _ => return None,
             };
 
-            if nested_path.is_ident("hashed_unique") {
-                return Some((Ordering::Hashed, Uniqueness::Unique));
-            } else if nested_path.is_ident("ordered_unique") {
-                return Some((Ordering::Ordered, Uniqueness::Unique));
-            } else if nested_path.is_ident("hashed_non_unique") {
-                return Some((Ordering::Hashed, Uniqueness::NonUnique));
-            } else if nested_path.is_ident("ordered_non_unique") {
-                return Some((Ordering::Ordered, Uniqueness::NonUnique));
-            } else {
-                emit_error!(nested_path.span(), "Invalid multi_index attribute, should be one of [hashed_unique, ordered_unique, hashed_non_unique, ordered_non_unique]");
-                return None;
+            let mut key_source = KeySource::Field;
+            for (i, modifier) in meta_list.nested.iter().skip(1).enumerate() {
+                if i > 0 {
+                    emit_error!(
+                        modifier.span(),
+                        "Only one multi_index attribute modifier is supported per field"
+                    );
+                    break;
+                }
+                match modifier {
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip_none") => {
+                        key_source = KeySource::OptionalField
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("each") => {
+                        key_source = KeySource::Each
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("ranked") => {}
+                    _ => emit_error!(
+                        modifier.span(),
+                        "Invalid multi_index attribute modifier, should be one of [skip_none, each, ranked]"
+                    ),
+                }
             }
+
+            let (ordering, uniqueness) = parse_index_kind(nested_path)?;
+            return Some((ordering, uniqueness, key_source));
         }
     }
     None
 }
 
+// Whether the field is annotated with `#[multi_index(bitmap)]`.
+// Bitmap indexes are maintained separately from the other kinds of index, so are checked for before `get_index_kind`.
+pub(crate) fn is_bitmap_index(f: &Field) -> bool {
+    for attr in f.attrs.iter() {
+        if attr.path.is_ident("multi_index") {
+            let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+                return false;
+            };
+            match meta_list.nested.first() {
+                Some(NestedMeta::Meta(Meta::Path(p))) if p.is_ident("bitmap") => {}
+                _ => return false,
+            }
+            if let Some(modifier) = meta_list.nested.iter().nth(1) {
+                emit_error!(
+                    modifier.span(),
+                    "bitmap indexes do not support any multi_index attribute modifiers"
+                );
+            }
+            return true;
+        }
+    }
+    false
+}
+
+// Whether the field is annotated with the `ranked` modifier, eg. `#[multi_index(ordered_unique, ranked)]`.
+// Ranked indexes are ordinary indexes which also maintain order statistics, so errors are left to `get_index_kind`.
+pub(crate) fn is_ranked_index(f: &Field) -> bool {
+    f.attrs.iter().any(|attr| {
+        if !attr.path.is_ident("multi_index") {
+            return false;
+        }
+        let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+            return false;
+        };
+        meta_list.nested.iter().skip(1).any(
+            |modifier| matches!(modifier, NestedMeta::Meta(Meta::Path(p)) if p.is_ident("ranked")),
+        )
+    })
+}
+
+// Get the type held by an Option, eg. `T` for `Option<T>`, this is the key type of `skip_none` indexes.
+// Types are only known syntactically here, so this matches any path ending in `Option<T>`.
+pub(crate) fn option_inner_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner.clone()),
+        _ => None,
+    }
+}
+
+// Get the type of the items in a collection, eg. `T` for `Vec<T>`, `HashSet<T, S>`, or `[T; N]`,
+//   this is the key type of `each` indexes.
+// Types are only known syntactically here, so for paths this is the first generic type argument.
+pub(crate) fn collection_item_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Array(array) => Some((*array.elem).clone()),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(item) => Some(item.clone()),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+// Parse a string literal from a `key = "value"` pair in a struct-level index attribute.
+fn parse_lit_str<T: syn::parse::Parse>(lit: &syn::Lit, what: &str) -> Option<T> {
+    match lit {
+        syn::Lit::Str(s) => match s.parse::<T>() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                emit_error!(s.span(), "Invalid {} in index attribute", what);
+                None
+            }
+        },
+        lit => {
+            emit_error!(
+                lit.span(),
+                "The {} in an index attribute must be a string literal",
+                what
+            );
+            None
+        }
+    }
+}
+
+// Parse the field identifiers listed in `fields(..)` in a struct-level index attribute.
+fn parse_field_list(l: &MetaList) -> Vec<syn::Ident> {
+    let mut fields = Vec::new();
+    for field in l.nested.iter() {
+        match field {
+            NestedMeta::Meta(Meta::Path(p)) if p.get_ident().is_some() => {
+                fields.push(p.get_ident().unwrap().clone())
+            }
+            _ => {
+                emit_error!(field.span(), "Index fields must be field identifiers")
+            }
+        }
+    }
+    fields
+}
+
+// Struct-level index attributes nest the settings of each index under its name, eg. `trader_ts(ordered_unique, ..)`,
+//   so settings repeated across several indexes, such as their kind, are not flagged by clippy's duplicated_attributes lint.
+// Get the name and settings of each index declared in the attribute, any other items are reported and skipped.
+fn parse_named_indexes(
+    attr: &syn::Attribute,
+    attr_name: &str,
+    example: &str,
+) -> Vec<(syn::Ident, Punctuated<NestedMeta, syn::token::Comma>)> {
+    let meta_list = match attr.parse_meta() {
+        Ok(syn::Meta::List(l)) => l,
+        _ => {
+            emit_error!(
+                attr.span(),
+                "Invalid {} attribute, expected a list eg. #[{}({})]",
+                attr_name,
+                attr_name,
+                example
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut indexes = Vec::new();
+    for nested in meta_list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::List(l)) if l.path.get_ident().is_some() => {
+                indexes.push((l.path.get_ident().unwrap().clone(), l.nested))
+            }
+            nested => emit_error!(
+                nested.span(),
+                "Invalid {} attribute, each index should be given by its name, eg. {}",
+                attr_name,
+                example
+            ),
+        }
+    }
+    indexes
+}
+
+// Get all the indexes declared on the struct with `#[multi_index_composite(..)]` or `#[multi_index_key(..)]`.
+// Each index is named by the list it is declared in, which must contain the index kind.
+// Composite indexes must also contain the list of `fields` the key is read from,
+//   and key extractor indexes the path of the extractor function in `fn`, and the key type in `ty`.
+pub(crate) fn get_struct_indexes(f: &DeriveInput) -> Vec<StructIndex> {
+    let mut indexes = Vec::new();
+
+    for attr in f.attrs.iter() {
+        let is_composite = attr.path.is_ident("multi_index_composite");
+        if !is_composite && !attr.path.is_ident("multi_index_key") {
+            continue;
+        }
+        let (attr_name, example) = if is_composite {
+            (
+                "multi_index_composite",
+                "trader_ts(ordered_unique, fields(trader_name, timestamp))",
+            )
+        } else {
+            (
+                "multi_index_key",
+                "symbol_prefix(hashed_non_unique, fn = \"Order::symbol_prefix\", ty = \"String\")",
+            )
+        };
+
+        for (name, settings) in parse_named_indexes(attr, attr_name, example) {
+            let mut kind = None;
+            let mut extractor = None;
+            let mut key_type = None;
+            let mut fields = Vec::new();
+
+            for nested in settings.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(p)) => kind = parse_index_kind(p),
+                    NestedMeta::Meta(Meta::NameValue(nv))
+                        if !is_composite && nv.path.is_ident("fn") =>
+                    {
+                        extractor = parse_lit_str::<Path>(&nv.lit, "fn")
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv))
+                        if !is_composite && nv.path.is_ident("ty") =>
+                    {
+                        key_type = parse_lit_str::<Type>(&nv.lit, "ty")
+                    }
+                    NestedMeta::Meta(Meta::List(l))
+                        if is_composite && l.path.is_ident("fields") =>
+                    {
+                        fields = parse_field_list(l)
+                    }
+                    _ => {
+                        if is_composite {
+                            emit_error!(
+                                nested.span(),
+                                "Invalid multi_index_composite attribute, should be one of [<index kind>, fields(..)]"
+                            )
+                        } else {
+                            emit_error!(
+                                nested.span(),
+                                "Invalid multi_index_key attribute, should be one of [<index kind>, fn = \"..\", ty = \"..\"]"
+                            )
+                        }
+                    }
+                }
+            }
+
+            let Some((ordering, uniqueness)) = kind else {
+                emit_error!(
+                    name.span(),
+                    "{} is missing an index kind, eg. ordered_unique",
+                    attr_name
+                );
+                continue;
+            };
+
+            let key_source = if is_composite {
+                if fields.len() < 2 {
+                    emit_error!(
+                        name.span(),
+                        "Composite index must list at least two fields, eg. fields(trader_name, timestamp)"
+                    );
+                    continue;
+                }
+                // Composite keys are looked up as a tuple of references, which the library supports up to twelve fields.
+                if fields.len() > 12 {
+                    emit_error!(
+                        name.span(),
+                        "Composite index may list at most twelve fields"
+                    );
+                    continue;
+                }
+                KeySource::Composite(fields.clone())
+            } else {
+                let Some(extractor) = extractor else {
+                    emit_error!(
+                        name.span(),
+                        "multi_index_key is missing the key function, eg. fn = \"Order::symbol_prefix\""
+                    );
+                    continue;
+                };
+                if key_type.is_none() {
+                    emit_error!(
+                        name.span(),
+                        "multi_index_key is missing the type returned by the key function, eg. ty = \"String\""
+                    );
+                    continue;
+                }
+                KeySource::Extractor(extractor)
+            };
+
+            indexes.push(StructIndex {
+                name,
+                fields,
+                key_type,
+                ordering,
+                uniqueness,
+                key_source,
+            });
+        }
+    }
+
+    indexes
+}
+
+// Represents an interval index declared on the struct,
+//   eg. `#[multi_index_interval(validity(fields(valid_from, valid_to)))]`
+// Each element is indexed by the half-open interval from its `start` field to its `end` field.
+pub(crate) struct IntervalIndex {
+    pub(crate) name: syn::Ident,
+    pub(crate) start: syn::Ident,
+    pub(crate) end: syn::Ident,
+}
+
+// Get all the interval indexes declared on the struct with `#[multi_index_interval(..)]`.
+// Each index is named by the list it is declared in, which must contain exactly two `fields`,
+//   the start and end of the interval, which must have the same type.
+pub(crate) fn get_interval_indexes(f: &DeriveInput) -> Vec<IntervalIndex> {
+    let mut indexes = Vec::new();
+
+    for attr in f.attrs.iter() {
+        if !attr.path.is_ident("multi_index_interval") {
+            continue;
+        }
+
+        for (name, settings) in parse_named_indexes(
+            attr,
+            "multi_index_interval",
+            "validity(fields(valid_from, valid_to))",
+        ) {
+            let mut fields = Vec::new();
+
+            for nested in settings.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("fields") => {
+                        fields = parse_field_list(l)
+                    }
+                    _ => emit_error!(
+                        nested.span(),
+                        "Invalid multi_index_interval attribute, should be one of [fields(start, end)]"
+                    ),
+                }
+            }
+
+            let [start, end]: [syn::Ident; 2] = match fields.try_into() {
+                Ok(fields) => fields,
+                Err(_) => {
+                    emit_error!(
+                        name.span(),
+                        "multi_index_interval must list exactly two fields, the start and end, eg. fields(valid_from, valid_to)"
+                    );
+                    continue;
+                }
+            };
+
+            indexes.push(IntervalIndex { name, start, end });
+        }
+    }
+
+    indexes
+}
+
 pub(crate) struct ExtraAttributes {
     pub(crate) derives: Vec<Meta>,
     pub(crate) hasher: syn::Path,
+    pub(crate) sequenced: bool,
+    pub(crate) random_access: bool,
+    pub(crate) generational: bool,
+    pub(crate) transactional: bool,
 }
 
 impl Default for ExtraAttributes {
@@ -65,6 +468,10 @@ impl Default for ExtraAttributes {
             hasher: syn::parse_quote!(::multi_index_map::rustc_hash::FxBuildHasher),
             #[cfg(not(feature = "rustc-hash"))]
             hasher: syn::parse_quote!(::std::hash::RandomState),
+            sequenced: false,
+            random_access: false,
+            generational: false,
+            transactional: false,
         }
     }
 }
@@ -139,6 +546,46 @@ pub(crate) fn get_extra_attributes(f: &DeriveInput) -> ExtraAttributes {
                 break;
             }
         }
+
+        if attr.path.is_ident("multi_index_sequenced") {
+            match attr.parse_meta() {
+                Ok(syn::Meta::Path(_)) => extra_attrs.sequenced = true,
+                _ => emit_error!(
+                    attr.span(),
+                    "Invalid multi_index_sequenced attribute, should be used without arguments, eg. #[multi_index_sequenced]"
+                ),
+            }
+        }
+
+        if attr.path.is_ident("multi_index_random_access") {
+            match attr.parse_meta() {
+                Ok(syn::Meta::Path(_)) => extra_attrs.random_access = true,
+                _ => emit_error!(
+                    attr.span(),
+                    "Invalid multi_index_random_access attribute, should be used without arguments, eg. #[multi_index_random_access]"
+                ),
+            }
+        }
+
+        if attr.path.is_ident("multi_index_generational") {
+            match attr.parse_meta() {
+                Ok(syn::Meta::Path(_)) => extra_attrs.generational = true,
+                _ => emit_error!(
+                    attr.span(),
+                    "Invalid multi_index_generational attribute, should be used without arguments, eg. #[multi_index_generational]"
+                ),
+            }
+        }
+
+        if attr.path.is_ident("multi_index_transactional") {
+            match attr.parse_meta() {
+                Ok(syn::Meta::Path(_)) => extra_attrs.transactional = true,
+                _ => emit_error!(
+                    attr.span(),
+                    "Invalid multi_index_transactional attribute, should be used without arguments, eg. #[multi_index_transactional]"
+                ),
+            }
+        }
     }
 
     extra_attrs
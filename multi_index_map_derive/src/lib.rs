@@ -1,17 +1,29 @@
 use ::proc_macro_error2::{abort_call_site, proc_macro_error};
 use ::quote::format_ident;
-use ::syn::{parse_macro_input, DeriveInput};
+use ::syn::{parse_macro_input, DeriveInput, Field, Ident};
 use convert_case::Casing;
 use generators::{generate_iter_mut, FieldIdents, EXPECT_NAMED_FIELDS};
-use proc_macro_error2::OptionExt;
-use syn::parse_quote;
+use index_attributes::{KeySource, Ordering, Uniqueness};
+use proc_macro_error2::{emit_error, OptionExt};
+use syn::{parse_quote, spanned::Spanned};
 
 mod generators;
 mod index_attributes;
 
 #[proc_macro_derive(
     MultiIndexMap,
-    attributes(multi_index, multi_index_derive, multi_index_hash)
+    attributes(
+        multi_index,
+        multi_index_derive,
+        multi_index_hash,
+        multi_index_composite,
+        multi_index_key,
+        multi_index_sequenced,
+        multi_index_random_access,
+        multi_index_generational,
+        multi_index_transactional,
+        multi_index_interval
+    )
 )]
 #[proc_macro_error]
 pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -20,6 +32,10 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 
     let extra_attrs = index_attributes::get_extra_attributes(&input);
 
+    let struct_indexes = index_attributes::get_struct_indexes(&input);
+
+    let interval_indexes = index_attributes::get_interval_indexes(&input);
+
     // Extract the struct fields if we are parsing a struct,
     // otherwise throw an error as we do not support Enums or Unions.
     let fields = match input.data {
@@ -36,10 +52,22 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         ),
     };
 
+    // The declared type of each field, as the type of an indexed field may be replaced by its key type below.
+    let declared_types = named_fields
+        .named
+        .iter()
+        .map(|f| (f.ident.clone(), f.ty.clone()))
+        .collect::<Vec<_>>();
+
+    // Bitmap indexes are maintained separately from the lookup tables of the other indexed fields.
+    let (bitmap_fields, fields): (Vec<_>, Vec<_>) = named_fields
+        .named
+        .into_iter()
+        .partition(index_attributes::is_bitmap_index);
+
     // Filter out all the fields that do not have a multi_index attribute,
     // so we can ignore the non-indexed fields.
-    let (indexed_fields, unindexed_fields): (Vec<_>, Vec<_>) = named_fields
-        .named
+    let (indexed_fields, unindexed_fields): (Vec<_>, Vec<_>) = fields
         .into_iter()
         .map(|f| {
             let index_kind = index_attributes::get_index_kind(&f);
@@ -52,56 +80,326 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     let map_name = format_ident!("MultiIndex{}Map", element_name);
 
     // Massage the two partitioned Vecs into the correct types
-    let indexed_fields = indexed_fields
+    let mut indexed_fields = indexed_fields
         .into_iter()
-        .map(|(field, kind)| {
-            let (ordering, uniqueness) = kind
+        .map(|(mut field, kind)| {
+            let (ordering, uniqueness, key_source) = kind
                 .expect_or_abort("Internal logic broken, all indexed fields should have a kind");
 
+            // The generators use the type of the field as the key type, which is the type inside the Option
+            //   for skip_none indexes, or the type of the items for each indexes.
+            match key_source {
+                KeySource::OptionalField => match index_attributes::option_inner_type(&field.ty) {
+                    Some(inner) => field.ty = inner,
+                    None => emit_error!(
+                        field.ty.span(),
+                        "skip_none indexes are only supported on fields of type Option<T>"
+                    ),
+                },
+                KeySource::Each => {
+                    match index_attributes::collection_item_type(&field.ty) {
+                        Some(item) => field.ty = item,
+                        None => emit_error!(
+                            field.ty.span(),
+                            "each indexes are only supported on collection fields, eg. Vec<T> or HashSet<T>"
+                        ),
+                    }
+                    // A single element holds several keys, which may be repeated within the collection,
+                    //   so the index must be able to hold several positions per key.
+                    if let Uniqueness::Unique = uniqueness {
+                        emit_error!(
+                            field.ident.span(),
+                            "each is only supported on non-unique indexes"
+                        );
+                    }
+                }
+                _ => {}
+            }
+
             let field_ident = field.ident.as_ref().expect_or_abort(EXPECT_NAMED_FIELDS);
+            let idents = field_idents(&map_name, field_ident);
 
-            let idents = FieldIdents {
-                name: field_ident.clone(),
-                index_name: format_ident!("_{field_ident}_index",),
-                cloned_name: format_ident!("{field_ident}_orig",),
-                iter_name: format_ident!(
-                    "{map_name}{}Iter",
-                    field_ident
-                        .to_string()
-                        .to_case(::convert_case::Case::UpperCamel),
-                ),
-            };
+            (field, idents, ordering, uniqueness, key_source)
+        })
+        .collect::<Vec<_>>();
 
-            (field, idents, ordering, uniqueness)
+    // Ranked indexes keep order statistics in addition to their ordinary lookup table.
+    // Combining ranked with another modifier has already been reported by get_index_kind, so is skipped here.
+    let ranked_fields = indexed_fields
+        .iter()
+        .filter(|(field, _, _, _, key_source)| {
+            matches!(key_source, KeySource::Field) && index_attributes::is_ranked_index(field)
+        })
+        .filter_map(|(field, idents, ordering, ..)| {
+            if let Ordering::Hashed = ordering {
+                emit_error!(
+                    field.ident.span(),
+                    "ranked is only supported on ordered indexes"
+                );
+                return None;
+            }
+            Some((field.clone(), field_idents(&map_name, &idents.name)))
         })
         .collect::<Vec<_>>();
 
+    // Fields which a struct-level index is read from must not be mutated without updating that index,
+    //   so they are treated as indexed, even if they have no index of their own.
+    // The fields a key extractor reads cannot be known, so when there is one every field is treated as indexed.
+    let has_extractor = struct_indexes
+        .iter()
+        .any(|i| matches!(i.key_source, KeySource::Extractor(_)));
     let unindexed_fields = unindexed_fields
         .into_iter()
         .map(|(field, _)| field)
+        .filter(|f| {
+            !has_extractor
+                && !struct_indexes
+                    .iter()
+                    .any(|i| i.fields.iter().any(|ident| f.ident.as_ref() == Some(ident)))
+                && !interval_indexes
+                    .iter()
+                    .any(|i| f.ident.as_ref() == Some(&i.start) || f.ident.as_ref() == Some(&i.end))
+        })
+        .collect::<Vec<_>>();
+
+    // Struct-level indexes do not correspond to a single field, so generate a Field with the type of the key,
+    //   which the generators treat the same as any indexed field, reading the key according to the KeySource.
+    // Composite indexes are keyed on a tuple of their fields, key extractor indexes on the given type.
+    for struct_index in struct_indexes {
+        let mut field_types = Vec::with_capacity(struct_index.fields.len());
+        for ident in struct_index.fields.iter() {
+            let field_type = declared_types
+                .iter()
+                .find(|(f_ident, _)| f_ident.as_ref() == Some(ident));
+            match field_type {
+                Some((_, ty)) => field_types.push(ty.clone()),
+                None => emit_error!(
+                    ident.span(),
+                    "Index field '{}' is not a field of this struct",
+                    ident
+                ),
+            }
+        }
+        if field_types.len() != struct_index.fields.len() {
+            continue;
+        }
+
+        if indexed_fields
+            .iter()
+            .any(|(_, idents, ..)| idents.name == struct_index.name)
+        {
+            emit_error!(
+                struct_index.name.span(),
+                "Index name '{}' clashes with another index",
+                struct_index.name
+            );
+            continue;
+        }
+
+        let field = Field {
+            attrs: Vec::new(),
+            vis: input.vis.clone(),
+            ident: Some(struct_index.name.clone()),
+            colon_token: None,
+            ty: struct_index
+                .key_type
+                .unwrap_or_else(|| parse_quote!((#(#field_types,)*))),
+        };
+
+        let idents = field_idents(&map_name, &struct_index.name);
+
+        indexed_fields.push((
+            field,
+            idents,
+            struct_index.ordering,
+            struct_index.uniqueness,
+            struct_index.key_source,
+        ));
+    }
+
+    if !bitmap_fields.is_empty() && !cfg!(feature = "bitmap") {
+        emit_error!(
+            bitmap_fields[0].ident.span(),
+            "bitmap indexes require the `bitmap` feature of multi_index_map to be enabled"
+        );
+    }
+    let bitmap_fields = bitmap_fields
+        .into_iter()
+        .map(|field| {
+            let field_ident = field.ident.as_ref().expect_or_abort(EXPECT_NAMED_FIELDS);
+            let idents = field_idents(&map_name, field_ident);
+            (field, idents)
+        })
         .collect::<Vec<_>>();
 
-    let lookup_table_fields = generators::generate_lookup_tables(&indexed_fields, &extra_attrs);
+    // Interval indexes are keyed on the type of their start and end fields.
+    let mut intervals = Vec::new();
+    for interval in interval_indexes {
+        let [start_type, end_type] = [&interval.start, &interval.end].map(|ident| {
+            let ty = declared_types
+                .iter()
+                .find(|(f_ident, _)| f_ident.as_ref() == Some(ident))
+                .map(|(_, ty)| ty.clone());
+            if ty.is_none() {
+                emit_error!(
+                    ident.span(),
+                    "Index field '{}' is not a field of this struct",
+                    ident
+                );
+            }
+            ty
+        });
+        // Both fields are compared against the same key, so must have the same type.
+        // Types are compared by their tokens, as syn only implements Eq for them with the extra-traits feature.
+        let key_type = match (start_type, end_type) {
+            (Some(start_type), Some(end_type)) => {
+                if quote::quote!(#start_type).to_string() != quote::quote!(#end_type).to_string() {
+                    emit_error!(
+                        end_type.span(),
+                        "The end field '{}' of an interval index must have the same type as its start field '{}'",
+                        interval.end,
+                        interval.start
+                    );
+                    None
+                } else {
+                    Some(start_type)
+                }
+            }
+            _ => None,
+        };
+        if indexed_fields
+            .iter()
+            .map(|(_, idents, ..)| idents)
+            .chain(bitmap_fields.iter().map(|(_, idents)| idents))
+            .any(|idents| idents.name == interval.name)
+        {
+            emit_error!(
+                interval.name.span(),
+                "Index name '{}' clashes with another index",
+                interval.name
+            );
+            continue;
+        }
+        if let Some(key_type) = key_type {
+            let idents = field_idents(&map_name, &interval.name);
+            intervals.push((interval, idents, key_type));
+        }
+    }
+
+    // The methods accessing elements by ElementId are named like the accessors of an index named element_id.
+    for idents in indexed_fields
+        .iter()
+        .map(|(_, idents, ..)| idents)
+        .chain(bitmap_fields.iter().map(|(_, idents)| idents))
+        .filter(|idents| idents.name == "element_id")
+    {
+        emit_error!(
+            idents.name.span(),
+            "Index name 'element_id' clashes with the methods accessing elements by ElementId"
+        );
+    }
+
+    // Rolling back re-inserts removed elements, which would move them to the back of these indexes.
+    if extra_attrs.transactional && (extra_attrs.sequenced || extra_attrs.random_access) {
+        emit_error!(
+            input.ident.span(),
+            "multi_index_transactional is not supported alongside multi_index_sequenced or multi_index_random_access"
+        );
+    }
 
-    let lookup_table_fields_init = generators::generate_lookup_table_init(&indexed_fields);
+    // Likewise the methods accessing elements by ElementHandle are named like the accessors of an index named handle.
+    if extra_attrs.generational {
+        for idents in indexed_fields
+            .iter()
+            .map(|(_, idents, ..)| idents)
+            .chain(bitmap_fields.iter().map(|(_, idents)| idents))
+            .filter(|idents| idents.name == "handle")
+        {
+            emit_error!(
+                idents.name.span(),
+                "Index name 'handle' clashes with the methods accessing elements by ElementHandle"
+            );
+        }
+    }
 
-    let lookup_table_fields_default = generators::generate_lookup_table_init(&indexed_fields);
+    // Indexes which are maintained separately from the lookup tables of the indexed fields,
+    //   but updated alongside them.
+    let mut auxiliary_indexes = Vec::new();
+    for (field, idents) in bitmap_fields.iter() {
+        auxiliary_indexes.push(generators::generate_bitmap_index(
+            field,
+            idents,
+            &extra_attrs,
+        ));
+    }
+    for (interval, idents, key_type) in intervals.iter() {
+        auxiliary_indexes.push(generators::generate_interval_index(
+            interval, idents, key_type,
+        ));
+    }
+    for (field, idents) in ranked_fields.iter() {
+        auxiliary_indexes.push(generators::generate_ranked_index(field, idents));
+    }
+    if extra_attrs.sequenced {
+        auxiliary_indexes.push(generators::generate_sequenced_index());
+    }
+    if extra_attrs.random_access {
+        auxiliary_indexes.push(generators::generate_random_access_index());
+    }
+    if extra_attrs.generational {
+        auxiliary_indexes.push(generators::generate_generations());
+    }
 
-    let lookup_table_fields_reserve = generators::generate_lookup_table_reserve(&indexed_fields);
+    let lookup_table_fields = generators::generate_lookup_tables(&indexed_fields, &extra_attrs)
+        .chain(
+            auxiliary_indexes
+                .iter()
+                .map(|a| a.lookup_table_field.clone()),
+        );
 
-    let lookup_table_fields_shrink = generators::generate_lookup_table_shrink(&indexed_fields);
+    let lookup_table_fields_init = generators::generate_lookup_table_init(&indexed_fields).chain(
+        auxiliary_indexes
+            .iter()
+            .map(|a| a.lookup_table_init.clone()),
+    );
+
+    let lookup_table_fields_default = generators::generate_lookup_table_init(&indexed_fields)
+        .chain(
+            auxiliary_indexes
+                .iter()
+                .map(|a| a.lookup_table_init.clone()),
+        );
+
+    let lookup_table_fields_reserve = generators::generate_lookup_table_reserve(&indexed_fields)
+        .chain(auxiliary_indexes.iter().map(|a| a.reserve.clone()));
+
+    let lookup_table_fields_shrink = generators::generate_lookup_table_shrink(&indexed_fields)
+        .chain(auxiliary_indexes.iter().map(|a| a.shrink.clone()));
 
-    let entries_for_insert = generators::generate_entries_for_insert(&indexed_fields);
+    let bitmap_position_check = if bitmap_fields.is_empty() {
+        None
+    } else {
+        Some(generators::generate_bitmap_position_check())
+    };
+    let entries_for_insert = bitmap_position_check
+        .into_iter()
+        .chain(generators::generate_entries_for_insert(&indexed_fields));
 
-    let inserts_for_entries = generators::generate_inserts_for_entries(&indexed_fields);
+    let inserts_for_entries = generators::generate_inserts_for_entries(&indexed_fields)
+        .chain(auxiliary_indexes.iter().map(|a| a.insert.clone()));
 
-    let removes = generators::generate_removes(&indexed_fields);
+    let mut removes = generators::generate_removes(&indexed_fields);
+    removes.extend(auxiliary_indexes.iter().map(|a| a.remove.clone()));
 
-    let pre_modifies = generators::generate_pre_modifies(&indexed_fields);
+    let mut pre_modifies = generators::generate_pre_modifies(&indexed_fields);
+    pre_modifies.extend(auxiliary_indexes.iter().map(|a| a.pre_modify.clone()));
 
-    let post_modifies = generators::generate_post_modifies(&indexed_fields);
+    let mut post_modifies = generators::generate_post_modifies(&indexed_fields);
+    post_modifies.extend(auxiliary_indexes.iter().map(|a| a.post_modify.clone()));
 
-    let clears = generators::generate_clears(&indexed_fields);
+    let clears = generators::generate_clears(&indexed_fields)
+        .chain(auxiliary_indexes.iter().map(|a| a.clear.clone()))
+        .collect::<Vec<_>>();
 
     let unindexed_types = unindexed_fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
     let unindexed_idents = unindexed_fields
@@ -109,6 +407,13 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         .map(|f| f.ident.as_ref().expect_or_abort(EXPECT_NAMED_FIELDS))
         .collect::<Vec<_>>();
 
+    // Every field read by an index, which are the fields that cannot be mutated outside of modify_by_.
+    let (protected_idents, protected_types): (Vec<_>, Vec<_>) = declared_types
+        .iter()
+        .map(|(ident, ty)| (ident.as_ref().expect_or_abort(EXPECT_NAMED_FIELDS), ty))
+        .filter(|(ident, _)| !unindexed_idents.contains(ident))
+        .unzip();
+
     let mut iter_generics = input.generics.clone();
     iter_generics
         .params
@@ -125,15 +430,142 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         &iter_generics,
     );
 
+    let mut entry_generics = input.generics.clone();
+    entry_generics
+        .params
+        .push(parse_quote!('__mim_entry_lifetime));
+    let (entry_methods, entry_types) = generators::generate_entries(
+        &indexed_fields,
+        &map_name,
+        element_name,
+        &unindexed_types,
+        &unindexed_idents,
+        &removes,
+        &pre_modifies,
+        &post_modifies,
+        &input.generics,
+        &entry_generics,
+    );
+
+    let mut transaction_generics = input.generics.clone();
+    transaction_generics
+        .params
+        .push(parse_quote!('__mim_transaction_lifetime));
+    let (transaction_methods, transaction_types) = if extra_attrs.transactional {
+        generators::generate_transaction(
+            &indexed_fields,
+            &map_name,
+            element_name,
+            &input.vis,
+            &unindexed_types,
+            &input.generics,
+            &transaction_generics,
+        )
+    } else {
+        Default::default()
+    };
+
     let iterators = generators::generate_iterators(
         &indexed_fields,
         element_name,
         &input.generics,
         &iter_generics,
-    );
+    )
+    .chain([entry_types, transaction_types]);
 
     let element_vis = input.vis;
 
+    let mut auxiliary_methods = vec![
+        generators::generate_uniqueness_conflicts(&indexed_fields, element_name, &input.generics),
+        generators::generate_try_modify_helpers(
+            &indexed_fields,
+            element_name,
+            &protected_idents,
+            &protected_types,
+            &pre_modifies,
+            &post_modifies,
+            &input.generics,
+        ),
+        generators::generate_insert_or_replace(
+            &indexed_fields,
+            element_name,
+            &element_vis,
+            &removes,
+            &input.generics,
+        ),
+        generators::generate_try_extend(
+            &indexed_fields,
+            element_name,
+            &element_vis,
+            &input.generics,
+        ),
+        generators::generate_element_id_methods(
+            element_name,
+            &element_vis,
+            &unindexed_types,
+            &unindexed_idents,
+            &removes,
+            &pre_modifies,
+            &post_modifies,
+            &input.generics,
+        ),
+        generators::generate_retain_methods(element_name, &element_vis, &removes, &input.generics),
+        entry_methods,
+        transaction_methods,
+    ];
+    if !bitmap_fields.is_empty() {
+        auxiliary_methods.push(generators::generate_bitmap_methods(
+            &bitmap_fields,
+            element_name,
+            &element_vis,
+            &removes,
+            &pre_modifies,
+            &post_modifies,
+            &input.generics,
+        ));
+    }
+    if !intervals.is_empty() {
+        auxiliary_methods.push(generators::generate_interval_methods(
+            &intervals,
+            element_name,
+            &element_vis,
+            &input.generics,
+        ));
+    }
+    if !ranked_fields.is_empty() {
+        auxiliary_methods.push(generators::generate_ranked_methods(
+            &ranked_fields,
+            element_name,
+            &input.generics,
+        ));
+    }
+    if extra_attrs.sequenced {
+        auxiliary_methods.push(generators::generate_sequenced_methods(
+            &indexed_fields,
+            element_name,
+            &element_vis,
+            &removes,
+            &input.generics,
+        ));
+    }
+    if extra_attrs.random_access {
+        auxiliary_methods.push(generators::generate_random_access_methods(
+            &indexed_fields,
+            element_name,
+            &element_vis,
+            &removes,
+            &input.generics,
+        ));
+    }
+    if extra_attrs.generational {
+        auxiliary_methods.push(generators::generate_handle_methods(
+            element_name,
+            &element_vis,
+            &unindexed_types,
+            &input.generics,
+        ));
+    }
+
     let iter_mut_name = format_ident!("{}IterMut", element_name);
     let iter_mut = generate_iter_mut(
         &iter_mut_name,
@@ -153,9 +585,9 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         &element_vis,
         entries_for_insert,
         inserts_for_entries,
-        accessors,
+        accessors.chain(auxiliary_methods),
         iterators,
-        clears,
+        &clears,
         lookup_table_fields,
         lookup_table_fields_init,
         lookup_table_fields_default,
@@ -169,3 +601,19 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     // Hand the output tokens back to the compiler.
     proc_macro::TokenStream::from(expanded)
 }
+
+// Generate the identifiers used for the lookup table and accessors of the index with the given name.
+fn field_idents(map_name: &Ident, field_ident: &Ident) -> FieldIdents {
+    let camel_name = field_ident
+        .to_string()
+        .to_case(::convert_case::Case::UpperCamel);
+    FieldIdents {
+        name: field_ident.clone(),
+        index_name: format_ident!("_{field_ident}_index",),
+        cloned_name: format_ident!("{field_ident}_orig",),
+        iter_name: format_ident!("{map_name}{camel_name}Iter"),
+        entry_name: format_ident!("{map_name}{camel_name}Entry"),
+        occupied_entry_name: format_ident!("{map_name}{camel_name}OccupiedEntry"),
+        vacant_entry_name: format_ident!("{map_name}{camel_name}VacantEntry"),
+    }
+}